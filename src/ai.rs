@@ -0,0 +1,216 @@
+use glam::Vec2;
+use rand::RngExt;
+use crate::controller::PaddleController;
+use crate::engine::{GameRng, Scene};
+use crate::physics::ARENA_HALF_HEIGHT;
+use crate::scoring::Player;
+
+/// En dessous de cet écart, la raquette ralentit pour ne pas osciller autour de sa cible
+const AIM_TOLERANCE: f32 = 0.05;
+
+#[derive(Clone, Copy, Debug)]
+pub struct AiDifficulty {
+    /// Temps avant de réagir quand la balle repart vers l'IA (secondes)
+    pub reaction_delay: f32,
+    /// Fraction de la vitesse maximale des raquettes utilisée par l'IA
+    pub max_speed: f32,
+    /// Erreur maximale sur le point d'interception prévu
+    pub prediction_error: f32,
+    /// Probabilité de viser volontairement à côté de la balle
+    pub miss_chance: f32,
+}
+
+impl AiDifficulty {
+    pub fn easy() -> Self {
+        Self {
+            reaction_delay: 0.35,
+            max_speed: 0.6,
+            prediction_error: 0.15,
+            miss_chance: 0.2,
+        }
+    }
+
+    pub fn normal() -> Self {
+        Self {
+            reaction_delay: 0.2,
+            max_speed: 0.8,
+            prediction_error: 0.08,
+            miss_chance: 0.08,
+        }
+    }
+
+    pub fn hard() -> Self {
+        Self {
+            reaction_delay: 0.08,
+            max_speed: 1.0,
+            prediction_error: 0.02,
+            miss_chance: 0.0,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "easy" => Some(Self::easy()),
+            "normal" => Some(Self::normal()),
+            "hard" => Some(Self::hard()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for AiDifficulty {
+    fn default() -> Self {
+        Self::normal()
+    }
+}
+
+/// Ordonnée où la balle atteindra `target_x`, en tenant compte des rebonds sur les murs.
+/// `half_height` est la demi-hauteur accessible au centre de la balle.
+pub fn predict_intercept(position: Vec2, velocity: Vec2, target_x: f32, half_height: f32) -> Option<f32> {
+    let time = (target_x - position.x) / velocity.x;
+    if !time.is_finite() || time < 0.0 {
+        return None;
+    }
+
+    let y = position.y + velocity.y * time;
+    if half_height <= 0.0 {
+        return Some(0.0);
+    }
+
+    // On replie la trajectoire rectiligne dans le couloir [-h, h]
+    let period = 4.0 * half_height;
+    let folded = (y + half_height).rem_euclid(period);
+    let folded = if folded > 2.0 * half_height { period - folded } else { folded };
+
+    Some(folded - half_height)
+}
+
+pub struct AiController {
+    pub difficulty: AiDifficulty,
+    reaction_timer: f32,
+    approaching: bool,
+    aim_offset: f32,
+    target_y: f32,
+}
+
+impl AiController {
+    pub fn new(difficulty: AiDifficulty) -> Self {
+        Self {
+            difficulty,
+            reaction_timer: 0.0,
+            approaching: false,
+            aim_offset: 0.0,
+            target_y: 0.0,
+        }
+    }
+
+    // Erreur de visée tirée au début de chaque approche ; `reach` est la portée de la raquette
+    fn draw_aim_offset(&self, rng: &mut GameRng, reach: f32) -> f32 {
+        if rng.random_bool(self.difficulty.miss_chance.clamp(0.0, 1.0) as f64) {
+            let side = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+            return side * reach * 1.5;
+        }
+
+        let error = self.difficulty.prediction_error;
+        if error > 0.0 {
+            rng.random_range(-error..=error)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl PaddleController for AiController {
+    fn intent(&mut self, player: Player, scene: &Scene, rng: &mut GameRng, dt: f32) -> f32 {
+        let ball = scene.component(scene.ball);
+        let paddle = scene.component(scene.paddle(player));
+
+        // La face avant de la raquette est tournée vers le centre
+        let facing = -paddle.position.x.signum();
+        let approaching = ball.velocity.x * facing < 0.0;
+
+        if approaching && !self.approaching {
+            self.reaction_timer = self.difficulty.reaction_delay;
+            self.aim_offset = self.draw_aim_offset(rng, paddle.collider.half_size.y + ball.collider.half_size.y);
+        }
+        self.approaching = approaching;
+
+        if self.reaction_timer > 0.0 {
+            self.reaction_timer -= dt;
+        } else if approaching {
+            let target_x = paddle.position.x + facing * (paddle.collider.half_size.x + ball.collider.half_size.x);
+            let half_height = ARENA_HALF_HEIGHT - ball.collider.half_size.y;
+
+            if let Some(y) = predict_intercept(ball.position, ball.velocity, target_x, half_height) {
+                self.target_y = y + self.aim_offset;
+            }
+        } else {
+            // Balle qui s'éloigne : retour au centre
+            self.target_y = 0.0;
+        }
+
+        let direction = ((self.target_y - paddle.position.y) / AIM_TOLERANCE).clamp(-1.0, 1.0);
+        direction * self.difficulty.max_speed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::vec2;
+
+    const HALF_HEIGHT: f32 = 0.9;
+
+    // Référence : avance la balle à petits pas en la réfléchissant sur les murs (en f64 pour
+    // que l'erreur accumulée reste sous la tolérance)
+    fn simulate_intercept(position: Vec2, velocity: Vec2, target_x: f32) -> f32 {
+        let (mut x, mut y) = (position.x as f64, position.y as f64);
+        let (vx, mut vy) = (velocity.x as f64, velocity.y as f64);
+        let (target_x, half_height) = (target_x as f64, HALF_HEIGHT as f64);
+        let dt = 1e-5;
+
+        while (target_x - x) * vx.signum() > 0.0 {
+            x += vx * dt;
+            y += vy * dt;
+            if y.abs() > half_height {
+                y = y.signum() * 2.0 * half_height - y;
+                vy = -vy;
+            }
+        }
+        y as f32
+    }
+
+    fn assert_matches_simulation(position: Vec2, velocity: Vec2, target_x: f32) {
+        let predicted = predict_intercept(position, velocity, target_x, HALF_HEIGHT).unwrap();
+        let simulated = simulate_intercept(position, velocity, target_x);
+        assert!(
+            (predicted - simulated).abs() < 1e-3,
+            "predicted {predicted}, simulated {simulated}"
+        );
+    }
+
+    #[test]
+    fn straight_shot_needs_no_bounce() {
+        assert_matches_simulation(vec2(0.0, 0.1), vec2(1.0, 0.2), 0.8);
+        assert_matches_simulation(vec2(0.0, -0.3), vec2(-1.0, 0.0), -0.8);
+    }
+
+    #[test]
+    fn single_bounce_is_folded_back() {
+        // Touche le mur du haut une fois avant d'arriver
+        assert_matches_simulation(vec2(0.0, 0.5), vec2(1.0, 1.0), 0.8);
+        assert_matches_simulation(vec2(0.2, -0.5), vec2(-1.0, -1.5), -0.8);
+    }
+
+    #[test]
+    fn multiple_bounces_are_folded_back() {
+        assert_matches_simulation(vec2(-0.8, 0.0), vec2(0.5, 2.0), 0.8);
+        assert_matches_simulation(vec2(0.8, 0.7), vec2(-0.3, -3.1), -0.8);
+    }
+
+    #[test]
+    fn ball_moving_away_has_no_intercept() {
+        assert_eq!(predict_intercept(vec2(0.0, 0.0), vec2(-1.0, 0.3), 0.8, HALF_HEIGHT), None);
+        assert_eq!(predict_intercept(vec2(0.0, 0.0), vec2(0.0, 1.0), 0.8, HALF_HEIGHT), None);
+    }
+}
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
@@ -5,12 +7,125 @@ use winit::event::{ElementState, KeyEvent, WindowEvent};
 use winit::event_loop::ActiveEventLoop;
 use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::window::{Window, WindowId};
+use render_backend::backend::{BackendChoice, GraphicsConfig};
+use render_backend::renderer::RenderError;
 use render_backend::state::State;
 use crate::render_backend;
+use crate::game_state::GameEvent;
+use crate::ai::{AiController, AiDifficulty};
+use crate::cli::{arg, arg_value, flag};
+use crate::client::GameClient;
+use crate::controller::KeyboardController;
+use crate::engine::Engine;
+use crate::input::{Action, InputMap, InputState, Rebinding};
+use crate::net::{NetConfig, NetInput, NetSession};
+use crate::protocol::Role;
+use crate::replay::ReplayPlayer;
+use crate::scoring::{MatchRules, Player};
+use crate::spectator::{Recording, StreamPlayer};
+
+const CONTROLS_FILE: &str = "controls.cfg";
+const GRAPHICS_FILE: &str = "graphics.cfg";
+const NET_DEFAULT_BIND: &str = "0.0.0.0:7001";
+/// Vitesses de lecture proposées par ReplaySlower / ReplayFaster
+const PLAYBACK_SPEEDS: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+/// Saut de ReplayBack / ReplayForward, en secondes de jeu
+const PLAYBACK_SEEK: f32 = 5.0;
+
+/// Replay rejoué par le moteur, ou flux spectateur affiché image par image
+enum Playback {
+    Replay(Box<ReplayPlayer>),
+    Stream(StreamPlayer),
+}
+
+impl Playback {
+    fn load(path: &str) -> anyhow::Result<Self> {
+        Ok(match Recording::load(path)? {
+            Recording::Replay(replay) => Playback::Replay(Box::new(ReplayPlayer::new(replay))),
+            Recording::Stream(recording) => Playback::Stream(StreamPlayer::new(recording)),
+        })
+    }
+
+    fn update(&mut self, state: &mut State, dt: std::time::Duration) {
+        match self {
+            Playback::Replay(player) => {
+                player.update(dt);
+                state.sync_with(&player.engine);
+            }
+            Playback::Stream(player) => {
+                player.update(&mut state.engine, dt);
+                state.sync();
+            }
+        }
+    }
+
+    /// Applique une touche de lecture, les autres sont ignorées
+    fn control(&mut self, action: Action, engine: &mut Engine) {
+        let (tick, tick_rate, speed) = match self {
+            Playback::Replay(player) => (player.tick(), player.engine.tick_rate, player.speed()),
+            Playback::Stream(player) => (player.tick(), player.recording().tick_rate, player.speed()),
+        };
+        let seek = (PLAYBACK_SEEK * tick_rate) as u64;
+
+        let target = match action {
+            Action::Pause => {
+                match self {
+                    Playback::Replay(player) => player.toggle_pause(),
+                    Playback::Stream(player) => player.toggle_pause(),
+                }
+                return;
+            }
+            Action::ReplaySlower | Action::ReplayFaster => {
+                let speed = if action == Action::ReplayFaster {
+                    PLAYBACK_SPEEDS.into_iter().find(|&s| s > speed)
+                } else {
+                    PLAYBACK_SPEEDS.into_iter().rev().find(|&s| s < speed)
+                }
+                .unwrap_or(speed);
+                match self {
+                    Playback::Replay(player) => player.set_speed(speed),
+                    Playback::Stream(player) => player.set_speed(speed),
+                }
+                log::info!("Playback speed x{}", speed);
+                return;
+            }
+            Action::ReplayStep => tick + 1,
+            Action::ReplayBack => tick.saturating_sub(seek),
+            Action::ReplayForward => tick + seek,
+            _ => return,
+        };
+
+        match self {
+            Playback::Replay(player) if action == Action::ReplayStep => player.step_once(),
+            Playback::Replay(player) => player.seek(target),
+            Playback::Stream(player) => player.seek(engine, target),
+        }
+    }
+}
 
 pub struct App {
     state: Option<State>,
     last_time: instant::Instant,
+    pub input_map: InputMap,
+    input_state: Rc<RefCell<InputState>>,
+    /// Réassignation de touche en cours (`Rebind`), sauvegardée dans `controls.cfg` une fois finie
+    rebinding: Option<Rebinding>,
+    /// Backend graphique (`graphics.cfg`, `RS_PONG_BACKEND`, `--backend=`, `--fallback-adapter`)
+    pub graphics: GraphicsConfig,
+    /// Mode un joueur : l'ordinateur contrôle la raquette droite
+    pub ai: Option<AiDifficulty>,
+    /// Fichier où sauvegarder la partie en quittant (`--record=fichier`), ou le flux reçu en spectateur
+    pub record_path: Option<String>,
+    /// Replay ou flux spectateur à visionner à la place d'une partie (`--replay=fichier`)
+    pub replay_path: Option<String>,
+    playback: Option<Playback>,
+    /// Partie en ligne (`--net-peer=adresse`, `--net-bind=`, `--net-player=1|2`, `--net-delay=`, `--net-seed=`)
+    net: Option<NetSession>,
+    net_seed: u64,
+    /// Client d'un serveur dédié (`--server=adresse`, `--server-role=1|2|spectator`)
+    client: Option<GameClient>,
+    /// Appuis sur Servir et Pause pas encore transmis à la session réseau ou au serveur
+    net_buttons: NetInput,
 }
 
 impl App {
@@ -18,10 +133,75 @@ impl App {
         Self {
             state: None,
             last_time: instant::Instant::now(),
+            input_map: load_controls(),
+            input_state: Rc::new(RefCell::new(InputState::default())),
+            rebinding: None,
+            graphics: load_graphics(),
+            ai: arg_value("ai").and_then(|name| AiDifficulty::from_name(&name)),
+            record_path: arg_value("record"),
+            replay_path: arg_value("replay"),
+            playback: None,
+            net: connect_net(),
+            net_seed: arg("net-seed", 0),
+            client: connect_server(),
+            net_buttons: NetInput::default(),
+        }
+    }
+
+    fn is_online(&self) -> bool {
+        self.net.is_some() || self.client.is_some()
+    }
+
+    fn exit(&mut self, event_loop: &ActiveEventLoop) {
+        if let Some(client) = &mut self.client {
+            client.leave();
+            if let (Some(path), Some(recording)) = (&self.record_path, client.stop_recording()) {
+                match recording.save(path) {
+                    Ok(()) => log::info!("Stream saved to {}", path),
+                    Err(e) => log::error!("Cannot save stream to {}: {}", path, e),
+                }
+            }
+        } else if let (Some(path), Some(state)) = (&self.record_path, &mut self.state)
+            && let Some(replay) = state.engine.stop_recording()
+        {
+            match replay.save(path) {
+                Ok(()) => log::info!("Replay saved to {}", path),
+                Err(e) => log::error!("Cannot save replay to {}: {}", path, e),
+            }
+        }
+        event_loop.exit();
+    }
+
+    // Pendant une réassignation, les appuis servent à choisir l'action puis sa nouvelle touche
+    fn rebind(&mut self, key: KeyCode) {
+        let Some(rebinding) = &mut self.rebinding else {
+            return;
+        };
+
+        match rebinding.press(&mut self.input_map, key) {
+            Ok(Some(action)) => {
+                self.rebinding = None;
+                log::info!("{} is now bound to {:?}", action.name(), key);
+                if let Err(e) = self.input_map.save(CONTROLS_FILE) {
+                    log::error!("Cannot save controls to {}: {}", CONTROLS_FILE, e);
+                }
+            }
+            Ok(None) => {
+                if let Rebinding::AwaitKey(action) = rebinding {
+                    log::info!("Press the new key for {}", action.name());
+                }
+            }
+            Err(e) => log::warn!("{}, press another key", e),
         }
     }
 }
 
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         let mut window_attributes = Window::default_attributes();
@@ -35,7 +215,42 @@ impl ApplicationHandler for App {
 
         #[cfg(not(target_arch = "wasm32"))]
         {
-            self.state = Some(pollster::block_on(State::new(window)).unwrap());
+            let mut state = match pollster::block_on(State::new(window, &self.graphics)) {
+                Ok(state) => state,
+                Err(e) => {
+                    log::error!("Cannot start renderer: {}", e);
+                    event_loop.exit();
+                    return;
+                }
+            };
+            state.engine.set_controller(
+                Player::One,
+                Box::new(KeyboardController::for_player(self.input_state.clone(), Player::One)),
+            );
+            match self.ai {
+                Some(difficulty) => state.engine.set_controller(Player::Two, Box::new(AiController::new(difficulty))),
+                None => state.engine.set_controller(
+                    Player::Two,
+                    Box::new(KeyboardController::for_player(self.input_state.clone(), Player::Two)),
+                ),
+            }
+
+            if self.net.is_some() {
+                // Même graine des deux côtés, et les raquettes ne sont pilotées que par la session
+                state.engine = Engine::with_seed(MatchRules::default(), self.net_seed);
+            } else if let Some(client) = &mut self.client {
+                if self.record_path.is_some() {
+                    client.start_recording(state.engine.tick_rate);
+                }
+            } else if let Some(path) = &self.replay_path {
+                match Playback::load(path) {
+                    Ok(playback) => self.playback = Some(playback),
+                    Err(e) => log::error!("Cannot load replay {}: {}", path, e),
+                }
+            } else if self.record_path.is_some() {
+                state.engine.start_recording();
+            }
+            self.state = Some(state);
         }
     }
 
@@ -45,13 +260,14 @@ impl ApplicationHandler for App {
         _window_id: WindowId,
         event: WindowEvent,
     ) {
+        let online = self.is_online();
         let state = match &mut self.state {
             Some(s) => s,
             None => return,
         };
 
         match event {
-            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::CloseRequested => self.exit(event_loop),
 
             WindowEvent::Resized(size) => state.resize(size.width, size.height),
 
@@ -59,13 +275,37 @@ impl ApplicationHandler for App {
                 let dt = self.last_time.elapsed();
                 self.last_time = instant::Instant::now();
 
-                state.update(dt);
+                if online {
+                    // Le joueur en ligne peut utiliser les touches de l'un ou l'autre côté
+                    let input_state = self.input_state.borrow();
+                    let paddle = input_state.axis(Action::P1Up, Action::P1Down)
+                        + input_state.axis(Action::P2Up, Action::P2Down);
+                    let input = NetInput {
+                        paddle: paddle.clamp(-1.0, 1.0),
+                        ..self.net_buttons
+                    };
+
+                    let steps = match (&mut self.net, &mut self.client) {
+                        (Some(net), _) => net.update(&mut state.engine, dt, input),
+                        (None, Some(client)) => client.update(&mut state.engine, dt, input),
+                        (None, None) => 0,
+                    };
+                    if steps > 0 {
+                        self.net_buttons = NetInput::default();
+                    }
+                    state.sync();
+                } else if let Some(playback) = &mut self.playback {
+                    playback.update(state, dt);
+                } else {
+                    state.update(dt);
+                }
 
                 match state.render() {
                     Ok(_) => {}
-                    Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
-                        let size = state.window.inner_size();
-                        state.resize(size.width, size.height);
+                    Err(RenderError::SurfaceLost) => {
+                        if let Some(size) = state.window.as_ref().map(|window| window.inner_size()) {
+                            state.resize(size.width, size.height);
+                        }
                     }
                     Err(e) => log::error!("Render error: {}", e),
                 }
@@ -76,39 +316,43 @@ impl ApplicationHandler for App {
                 event: KeyEvent {
                     physical_key: PhysicalKey::Code(code),
                     state: key_state,
+                    repeat,
                     ..
                 },
                 ..
             } => {
-                let speed = 0.03;
+                if self.rebinding.is_some() {
+                    match key_state {
+                        ElementState::Pressed if !repeat => self.rebind(code),
+                        ElementState::Released => self.input_state.borrow_mut().release(code),
+                        _ => {}
+                    }
+                    return;
+                }
 
-                if key_state == ElementState::Pressed {
-                    match code {
-                        KeyCode::Escape => event_loop.exit(),
+                let action = self.input_map.action(code);
 
-                        // Raquette gauch
-                        KeyCode::KeyW => {
-                            state.engine.physics.scene.player1.position.y += speed;
-                        }
-                        KeyCode::KeyS => {
-                            state.engine.physics.scene.player1.position.y -= speed;
-                        }
+                // Les raquettes lisent les actions maintenues via leur KeyboardController
+                match (key_state, action) {
+                    (ElementState::Pressed, Some(action)) => self.input_state.borrow_mut().press(code, action),
+                    _ => self.input_state.borrow_mut().release(code),
+                }
 
-                        // Raquette droite
-                        KeyCode::ArrowUp => {
-                            state.engine.physics.scene.player2.position.y += speed;
-                        }
-                        KeyCode::ArrowDown => {
-                            state.engine.physics.scene.player2.position.y -= speed;
+                if key_state == ElementState::Pressed {
+                    match action {
+                        Some(Action::Quit) => self.exit(event_loop),
+                        Some(Action::Rebind) if !repeat => {
+                            self.rebinding = Some(Rebinding::default());
+                            log::info!("Press the key of the action to rebind");
                         }
-
-                        // Reset balle
-                        KeyCode::Space => {
-                            use glam::vec2;
-                            state.engine.physics.scene.ball.position = vec2(0.0, 0.0);
-                            state.engine.physics.scene.ball.velocity = vec2(0.02, 0.015);
+                        Some(Action::Serve) if online => self.net_buttons.serve = true,
+                        Some(Action::Pause) if online => self.net_buttons.pause = true,
+                        // Pendant une lecture, seules les touches de lecture agissent
+                        Some(action) if self.playback.is_some() => {
+                            self.playback.as_mut().unwrap().control(action, &mut state.engine);
                         }
-
+                        Some(Action::Serve) => state.engine.handle_event(GameEvent::Serve),
+                        Some(Action::Pause) => state.engine.handle_event(GameEvent::TogglePause),
                         _ => {}
                     }
                 }
@@ -119,8 +363,81 @@ impl ApplicationHandler for App {
     }
 
     fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
-        if let Some(state) = &self.state {
-            Window::request_redraw(&state.window);
+        if let Some(window) = self.state.as_ref().and_then(|state| state.window.as_ref()) {
+            window.request_redraw();
+        }
+    }
+}
+
+// Contrôles personnalisés si le fichier existe, sinon ceux par défaut
+fn load_controls() -> InputMap {
+    if !std::path::Path::new(CONTROLS_FILE).exists() {
+        return InputMap::default();
+    }
+
+    InputMap::load(CONTROLS_FILE).unwrap_or_else(|e| {
+        log::error!("Invalid {}: {}, using default controls", CONTROLS_FILE, e);
+        InputMap::default()
+    })
+}
+
+// Le fichier de configuration, puis l'environnement, puis la ligne de commande
+fn load_graphics() -> GraphicsConfig {
+    let mut graphics = if std::path::Path::new(GRAPHICS_FILE).exists() {
+        GraphicsConfig::load(GRAPHICS_FILE).unwrap_or_else(|e| {
+            log::error!("Invalid {}: {}, using default graphics settings", GRAPHICS_FILE, e);
+            GraphicsConfig::default()
+        })
+    } else {
+        GraphicsConfig::default()
+    }
+    .with_env();
+
+    if let Some(name) = arg_value("backend") {
+        match BackendChoice::from_name(&name) {
+            Some(backend) => graphics.backend = backend,
+            None => log::error!("Unknown backend {}, using {}", name, graphics.backend.name()),
+        }
+    }
+    if flag("fallback-adapter") {
+        graphics.force_fallback_adapter = true;
+    }
+    graphics
+}
+
+fn connect_net() -> Option<NetSession> {
+    let peer = arg_value("net-peer")?;
+    let bind = arg_value("net-bind").unwrap_or_else(|| NET_DEFAULT_BIND.to_string());
+
+    let mut config = NetConfig::default();
+    if arg_value("net-player").as_deref() == Some("2") {
+        config.local_player = Player::Two;
+    }
+    config.input_delay = arg("net-delay", config.input_delay);
+
+    match NetSession::new(config, bind.as_str(), peer.as_str()) {
+        Ok(session) => Some(session),
+        Err(e) => {
+            log::error!("Cannot start online game on {} with {}: {}", bind, peer, e);
+            None
         }
     }
-}
\ No newline at end of file
+}
+
+fn connect_server() -> Option<GameClient> {
+    let server = arg_value("server")?;
+    let role = match arg_value("server-role").as_deref() {
+        Some("1") => Some(Role::Player(Player::One)),
+        Some("2") => Some(Role::Player(Player::Two)),
+        Some("spectator") => Some(Role::Spectator),
+        _ => None,
+    };
+
+    match GameClient::new("0.0.0.0:0", server.as_str(), role) {
+        Ok(client) => Some(client),
+        Err(e) => {
+            log::error!("Cannot connect to server {}: {}", server, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Match en réseau sans fenêtre entre deux processus, chaque raquette tenue par l'IA.
+//! Les deux processus doivent afficher la même empreinte finale.
+//!
+//! ```text
+//! net_match --player=1 --bind=127.0.0.1:7001 --peer=127.0.0.1:7002 --latency=40 --loss=0.1
+//! net_match --player=2 --bind=127.0.0.1:7002 --peer=127.0.0.1:7001 --latency=40 --loss=0.1
+//! ```
+
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use rand::SeedableRng;
+use rs_pong::ai::{AiController, AiDifficulty};
+use rs_pong::cli::arg;
+use rs_pong::controller::PaddleController;
+use rs_pong::engine::{Engine, GameRng};
+use rs_pong::net::{NetConditions, NetConfig, NetInput, NetSession};
+use rs_pong::scoring::{MatchRules, Player};
+
+/// Attente maximale du pair, au démarrage comme à la fin
+const PEER_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn main() -> ExitCode {
+    let player = match arg("player", 1u8) {
+        1 => Player::One,
+        2 => Player::Two,
+        _ => {
+            eprintln!("--player must be 1 or 2");
+            return ExitCode::from(2);
+        }
+    };
+    let bind: String = arg("bind", "127.0.0.1:7001".to_string());
+    let peer: String = arg("peer", "127.0.0.1:7002".to_string());
+    let frames: u64 = arg("frames", 1200);
+
+    let config = NetConfig {
+        local_player: player,
+        input_delay: arg("delay", 2),
+        max_prediction: arg("prediction", 8),
+        conditions: NetConditions {
+            latency: Duration::from_millis(arg("latency", 0)),
+            jitter: Duration::from_millis(arg("jitter", 0)),
+            loss: arg("loss", 0.0),
+        },
+    };
+
+    let mut session = match NetSession::new(config, bind.as_str(), peer.as_str()) {
+        Ok(session) => session,
+        Err(e) => {
+            eprintln!("cannot open {bind}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut engine = Engine::with_seed(MatchRules::default(), arg("seed", 1));
+    let dt = engine.fixed_dt();
+    let mut ai = AiController::new(AiDifficulty::normal());
+    let mut ai_rng = GameRng::seed_from_u64(player.index() as u64);
+
+    let started = Instant::now();
+    let mut next_tick = Instant::now();
+    while session.frame() < frames {
+        if session.peer_silence().is_none() && started.elapsed() > PEER_TIMEOUT {
+            eprintln!("no answer from {peer}");
+            return ExitCode::FAILURE;
+        }
+
+        // Le joueur 1 lance la partie dès le premier pas
+        let input = NetInput {
+            paddle: ai.intent(player, &engine.physics.scene, &mut ai_rng, dt),
+            serve: player == Player::One && session.frame() == 0,
+            pause: false,
+        };
+        if !session.advance(&mut engine, input) {
+            std::thread::sleep(Duration::from_millis(1));
+            continue;
+        }
+
+        next_tick += Duration::from_secs_f32(dt);
+        if let Some(wait) = next_tick.checked_duration_since(Instant::now()) {
+            std::thread::sleep(wait);
+        }
+    }
+
+    // Tout doit être confirmé des deux côtés avant de comparer
+    let waiting = Instant::now();
+    while session.confirmed_frame() < frames || session.acknowledged_frame() < frames {
+        if waiting.elapsed() > PEER_TIMEOUT {
+            eprintln!("peer stopped answering before frame {frames} was confirmed");
+            return ExitCode::FAILURE;
+        }
+        session.poll(&mut engine);
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    // Le pair attend peut-être encore notre accusé de réception
+    let linger = Instant::now();
+    while linger.elapsed() < Duration::from_millis(500) {
+        session.poll(&mut engine);
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    println!(
+        "player {} frame {} checksum {:016x} score {}-{} rollbacks {}",
+        player.index() + 1,
+        engine.tick(),
+        engine.physics.scene.checksum(),
+        engine.scoring.points(Player::One),
+        engine.scoring.points(Player::Two),
+        session.rollbacks(),
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,42 @@
+//! Serveur de jeu sans fenêtre : deux joueurs et autant de spectateurs que voulu, en UDP.
+//!
+//! ```text
+//! pong_server --bind=0.0.0.0:7000 --snapshot-rate=20 --spectator-delay=2000 --points=11
+//! ```
+
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use rs_pong::cli::arg;
+use rs_pong::scoring::MatchRules;
+use rs_pong::server::{GameServer, ServerConfig};
+
+fn main() -> ExitCode {
+    let bind: String = arg("bind", "0.0.0.0:7000".to_string());
+    let config = ServerConfig {
+        rules: MatchRules {
+            points_to_win: arg("points", 11),
+            best_of: arg("best-of", 1),
+            ..MatchRules::default()
+        },
+        snapshot_rate: arg("snapshot-rate", 20.0),
+        spectator_delay: Duration::from_millis(arg("spectator-delay", 2000)),
+        ..ServerConfig::default()
+    };
+
+    let mut server = match GameServer::new(config, bind.as_str()) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("cannot listen on {bind}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("listening on {}", server.local_addr().map_or(bind, |addr| addr.to_string()));
+
+    let mut last_time = Instant::now();
+    loop {
+        let now = Instant::now();
+        server.update(now - last_time);
+        last_time = now;
+        std::thread::sleep(Duration::from_millis(2));
+    }
+}
@@ -0,0 +1,107 @@
+//! Rend sans fenêtre des images PNG d'un replay ou d'un flux spectateur enregistré.
+//!
+//! ```text
+//! replay_thumbnails --replay=match.replay --out=thumbs --every=60 --width=320 --height=180
+//! ```
+//!
+//! `--backend=` et `--fallback-adapter` choisissent l'adaptateur, par exemple llvmpipe ou lavapipe en CI ;
+//! `--software` se passe de GPU avec le rendu logiciel, le seul disponible sans la feature `render`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use rs_pong::cli::{arg, flag};
+use rs_pong::engine::Engine;
+#[cfg(feature = "render")]
+use rs_pong::render_backend::backend::{BackendChoice, GraphicsConfig};
+use rs_pong::render_backend::state::State;
+use rs_pong::spectator::Recording;
+
+#[cfg(feature = "render")]
+fn offscreen(width: u32, height: u32) -> anyhow::Result<State> {
+    let mut graphics = GraphicsConfig::default().with_env();
+    if let Some(backend) = BackendChoice::from_name(&arg("backend", String::new())) {
+        graphics.backend = backend;
+    }
+    graphics.force_fallback_adapter |= flag("fallback-adapter");
+    pollster::block_on(State::offscreen(width, height, &graphics))
+}
+
+#[cfg(not(feature = "render"))]
+fn offscreen(_width: u32, _height: u32) -> anyhow::Result<State> {
+    anyhow::bail!("built without the `render` feature, only --software is available")
+}
+
+fn main() -> ExitCode {
+    let path: String = arg("replay", String::new());
+    if path.is_empty() {
+        eprintln!("usage: replay_thumbnails --replay=<file> [--out=dir] [--every=ticks] [--width=] [--height=]");
+        return ExitCode::from(2);
+    }
+    let out = PathBuf::from(arg("out", ".".to_string()));
+    let every: u64 = arg("every", 60).max(1);
+
+    let (width, height) = (arg("width", 320), arg("height", 180));
+    let software = !cfg!(feature = "render") || flag("software");
+    let state = if software {
+        Ok(State::software(width, height))
+    } else {
+        offscreen(width, height)
+    };
+    let mut state = match state {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("cannot start renderer: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(e) = std::fs::create_dir_all(&out) {
+        eprintln!("cannot create {}: {e}", out.display());
+        return ExitCode::FAILURE;
+    }
+
+    let capture = |state: &mut State, tick: u64| -> anyhow::Result<()> {
+        state.sync();
+        state.render()?;
+        let file = out.join(format!("frame_{tick:06}.png"));
+        state.read_pixels()?.save_png(&file)?;
+        println!("{}", file.display());
+        Ok(())
+    };
+
+    let result = Recording::load(&path).and_then(|recording| match recording {
+        // Flux spectateur : chaque image enregistrée porte déjà son pas
+        Recording::Stream(recording) => {
+            let mut next = 0;
+            for snapshot in &recording.frames {
+                if snapshot.tick >= next {
+                    snapshot.write_to(&mut state.engine.physics.scene);
+                    state.engine.reset_interpolation();
+                    capture(&mut state, snapshot.tick)?;
+                    next = snapshot.tick + every;
+                }
+            }
+            Ok(())
+        }
+        Recording::Replay(replay) => {
+            state.engine = Engine::from_replay(&replay);
+            let dt = state.engine.fixed_dt();
+            while !state.engine.replay_finished() {
+                let tick = state.engine.tick();
+                if tick.is_multiple_of(every) {
+                    capture(&mut state, tick)?;
+                }
+                state.engine.step(dt);
+            }
+            Ok(())
+        }
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{path}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Rejoue des fichiers replay sans fenêtre et vérifie leurs empreintes pas à pas.
+//! Un flux spectateur enregistré est relu image par image et ses pas doivent se suivre.
+//! Usage : `verify_replay <fichier>...` ; code de sortie 1 à la première divergence.
+
+use std::process::ExitCode;
+use rs_pong::spectator::Recording;
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: verify_replay <replay|stream>...");
+        return ExitCode::from(2);
+    }
+
+    let mut ok = true;
+    for path in &paths {
+        let replay = match Recording::load(path) {
+            Ok(Recording::Replay(replay)) => replay,
+            Ok(Recording::Stream(stream)) => {
+                match stream.verify() {
+                    Ok(frames) => println!("{path}: ok, stream of {frames} frames"),
+                    Err(e) => {
+                        println!("{path}: {e}");
+                        ok = false;
+                    }
+                }
+                continue;
+            }
+            Err(e) => {
+                eprintln!("{path}: {e}");
+                ok = false;
+                continue;
+            }
+        };
+
+        if replay.checksums.is_empty() {
+            println!("{path}: no checksums to verify ({} ticks)", replay.len());
+            continue;
+        }
+
+        match replay.verify() {
+            Ok(ticks) => println!("{path}: ok, {ticks} ticks"),
+            Err(divergence) => {
+                println!("{path}: diverged at {divergence}");
+                ok = false;
+            }
+        }
+    }
+
+    if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
@@ -1,6 +1,24 @@
 use glam::Vec2;
 use crate::render_backend::buffer::Vertex;
 
+/// Écart d'instant d'entrée en dessous duquel un impact est traité comme un coin
+const CORNER_EPSILON: f32 = 1e-5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SweepHit {
+    /// Instant d'impact, en fraction du déplacement (0.0 à 1.0)
+    pub time: f32,
+    pub normal: Vec2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    /// Normale unitaire dirigée de `other` vers `self`
+    pub normal: Vec2,
+    pub penetration: f32,
+    pub point: Vec2,
+}
+
 #[derive(Clone)]
 pub struct Collider {
     pub half_size: Vec2,
@@ -35,4 +53,196 @@ impl Collider {
             && self_max.y >= other_min.y
             && self_min.y <= other_max.y
     }
-}
\ No newline at end of file
+
+    /// Contact le long de l'axe de translation minimale, si les boîtes se chevauchent
+    pub fn contact(&self, self_pos: Vec2, other: &Collider, other_pos: Vec2) -> Option<Contact> {
+        let delta = self_pos - other_pos;
+        let overlap = self.half_size + other.half_size - delta.abs();
+
+        if overlap.x <= 0.0 || overlap.y <= 0.0 {
+            return None;
+        }
+
+        let (normal, penetration) = if overlap.x < overlap.y {
+            (Vec2::new(delta.x.signum(), 0.0), overlap.x)
+        } else {
+            (Vec2::new(0.0, delta.y.signum()), overlap.y)
+        };
+
+        // Centre de la zone de recouvrement
+        let min = (self_pos - self.half_size).max(other_pos - other.half_size);
+        let max = (self_pos + self.half_size).min(other_pos + other.half_size);
+
+        Some(Contact {
+            normal,
+            penetration,
+            point: (min + max) * 0.5,
+        })
+    }
+
+    /// Balaye `self` le long de `motion` contre `other` immobile et renvoie le premier impact
+    pub fn sweep(
+        &self,
+        self_pos: Vec2,
+        motion: Vec2,
+        other: &Collider,
+        other_pos: Vec2,
+    ) -> Option<SweepHit> {
+        if motion == Vec2::ZERO {
+            return None;
+        }
+
+        // Somme de Minkowski : on lance un rayon depuis self_pos contre la boîte élargie
+        let extent = self.half_size + other.half_size;
+        let min = other_pos - extent;
+        let max = other_pos + extent;
+
+        let mut entry = [f32::NEG_INFINITY; 2];
+        let mut exit = [f32::INFINITY; 2];
+
+        for axis in 0..2 {
+            let p = self_pos[axis];
+            let d = motion[axis];
+
+            if d == 0.0 {
+                if p <= min[axis] || p >= max[axis] {
+                    return None;
+                }
+                continue;
+            }
+
+            let t1 = (min[axis] - p) / d;
+            let t2 = (max[axis] - p) / d;
+            entry[axis] = t1.min(t2);
+            exit[axis] = t1.max(t2);
+        }
+
+        let t_entry = entry[0].max(entry[1]);
+        let t_exit = exit[0].min(exit[1]);
+
+        if t_entry > t_exit || t_exit <= 0.0 || t_entry > 1.0 {
+            return None;
+        }
+
+        // Sur un coin (entrées simultanées aux arrondis près), la normale verticale l'emporte
+        let normal = if entry[0] > entry[1] + CORNER_EPSILON {
+            Vec2::new(-motion.x.signum(), 0.0)
+        } else {
+            Vec2::new(0.0, -motion.y.signum())
+        };
+
+        // On ignore un contact dont on s'éloigne déjà
+        if motion.dot(normal) >= 0.0 {
+            return None;
+        }
+
+        Some(SweepHit {
+            time: t_entry.max(0.0),
+            normal,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::vec2;
+
+    fn ball() -> Collider {
+        Collider::new(vec2(0.02, 0.02), [1.0; 3])
+    }
+
+    fn paddle() -> Collider {
+        Collider::new(vec2(0.03, 0.2), [1.0; 3])
+    }
+
+    #[test]
+    fn fast_ball_does_not_tunnel_through_the_paddle() {
+        // 0.5 par pas, bien plus que la largeur de la raquette (0.06)
+        let motion = vec2(0.5, 0.0);
+        let hit = ball()
+            .sweep(vec2(0.5, 0.0), motion, &paddle(), vec2(0.8, 0.0))
+            .expect("the sweep crosses the paddle");
+
+        assert!((0.0..=1.0).contains(&hit.time));
+        assert_eq!(hit.normal, vec2(-1.0, 0.0));
+        // Au point d'impact, les boîtes se touchent juste
+        let impact = vec2(0.5, 0.0) + motion * hit.time;
+        assert!((impact.x - (0.8 - 0.05)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn sweep_that_misses_returns_none() {
+        let (ball, paddle) = (ball(), paddle());
+        // Passe au-dessus
+        assert_eq!(ball.sweep(vec2(0.5, 0.5), vec2(0.5, 0.0), &paddle, vec2(0.8, 0.0)), None);
+        // S'arrête avant
+        assert_eq!(ball.sweep(vec2(0.5, 0.0), vec2(0.1, 0.0), &paddle, vec2(0.8, 0.0)), None);
+        // S'éloigne
+        assert_eq!(ball.sweep(vec2(0.5, 0.0), vec2(-0.5, 0.0), &paddle, vec2(0.8, 0.0)), None);
+        // Immobile
+        assert_eq!(ball.sweep(vec2(0.5, 0.0), Vec2::ZERO, &paddle, vec2(0.8, 0.0)), None);
+    }
+
+    #[test]
+    fn corner_hit_has_a_single_stable_normal() {
+        let (ball, paddle) = (ball(), paddle());
+        // Vise exactement le coin bas gauche de la boîte élargie
+        let corner = vec2(0.8, 0.0) - (ball.half_size + paddle.half_size);
+        let start = corner - vec2(0.1, 0.1);
+        let motion = vec2(0.2, 0.2);
+
+        let hit = ball.sweep(start, motion, &paddle, vec2(0.8, 0.0)).expect("corner hit");
+        assert!((hit.time - 0.5).abs() < 1e-5);
+        assert_eq!(hit.normal, vec2(0.0, -1.0));
+
+        for _ in 0..10 {
+            assert_eq!(ball.sweep(start, motion, &paddle, vec2(0.8, 0.0)), Some(hit));
+        }
+    }
+
+    #[test]
+    fn contact_pushes_out_along_x() {
+        // La balle mord de 0.01 sur la face gauche de la raquette
+        let contact = ball()
+            .contact(vec2(0.76, 0.05), &paddle(), vec2(0.8, 0.0))
+            .expect("overlap");
+
+        assert_eq!(contact.normal, vec2(-1.0, 0.0));
+        assert!((contact.penetration - 0.01).abs() < 1e-5);
+        assert!((contact.point - vec2(0.775, 0.05)).length() < 1e-5);
+    }
+
+    #[test]
+    fn contact_pushes_out_along_y() {
+        // La balle mord de 0.005 sur le dessus de la raquette
+        let contact = ball()
+            .contact(vec2(0.8, 0.215), &paddle(), vec2(0.8, 0.0))
+            .expect("overlap");
+
+        assert_eq!(contact.normal, vec2(0.0, 1.0));
+        assert!((contact.penetration - 0.005).abs() < 1e-5);
+        assert!((contact.point - vec2(0.8, 0.1975)).length() < 1e-5);
+    }
+
+    #[test]
+    fn nested_contact_takes_the_shortest_way_out() {
+        let (ball, paddle) = (ball(), paddle());
+        // Balle entièrement dans la raquette, plus près du bord droit
+        let contact = ball.contact(vec2(0.81, 0.0), &paddle, vec2(0.8, 0.0)).expect("overlap");
+        assert_eq!(contact.normal, vec2(1.0, 0.0));
+        assert!((contact.penetration - 0.04).abs() < 1e-5);
+        // Le point de contact est le centre de la balle, contenue dans la raquette
+        assert!((contact.point - vec2(0.81, 0.0)).length() < 1e-5);
+
+        // Vu depuis la raquette, la normale s'inverse
+        let reverse = paddle.contact(vec2(0.8, 0.0), &ball, vec2(0.81, 0.0)).expect("overlap");
+        assert_eq!(reverse.normal, -contact.normal);
+        assert_eq!(reverse.penetration, contact.penetration);
+    }
+
+    #[test]
+    fn touching_boxes_have_no_contact() {
+        assert_eq!(ball().contact(vec2(0.75, 0.0), &paddle(), vec2(0.8, 0.0)), None);
+    }
+}
@@ -0,0 +1,20 @@
+//! Options de la ligne de commande, de la forme `--nom=valeur` ou `--nom`
+
+use std::str::FromStr;
+
+/// Valeur de `--name=valeur`, la première si l'option est répétée
+pub fn arg_value(name: &str) -> Option<String> {
+    let prefix = format!("--{name}=");
+    std::env::args().find_map(|arg| arg.strip_prefix(&prefix).map(str::to_string))
+}
+
+/// Valeur de `--name=valeur` convertie ; `default` si l'option est absente ou illisible
+pub fn arg<T: FromStr>(name: &str, default: T) -> T {
+    arg_value(name).and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+/// Vrai si l'option sans valeur `--name` est présente
+pub fn flag(name: &str) -> bool {
+    let option = format!("--{name}");
+    std::env::args().any(|arg| arg == option)
+}
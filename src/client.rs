@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+use crate::engine::Engine;
+use crate::game_state::GameState;
+use crate::headless::Snapshot;
+use crate::net::NetInput;
+use crate::protocol::{Message, Role};
+use crate::spectator::StreamRecording;
+
+/// Intervalle entre deux demandes de connexion, puis entre deux signes de vie d'un spectateur
+const JOIN_RETRY: Duration = Duration::from_millis(250);
+
+/// Client d'un `GameServer`.
+///
+/// L'`Engine` passé aux méthodes ne sert que d'affichage : il reçoit l'état du serveur, sa propre
+/// raquette est prédite localement à chaque pas, puis recalée sur chaque instantané en rejouant
+/// les entrées que le serveur n'a pas encore prises en compte.
+pub struct GameClient {
+    socket: UdpSocket,
+    server: SocketAddr,
+    requested: Option<Role>,
+    role: Option<Role>,
+    rejected: bool,
+    sequence: u32,
+    /// Entrées envoyées mais pas encore confirmées par le serveur
+    pending: VecDeque<(u32, NetInput)>,
+    snapshot: Option<Snapshot>,
+    recording: Option<StreamRecording>,
+    accumulator: f32,
+    last_sent: Option<Instant>,
+    last_received: Option<Instant>,
+}
+
+impl GameClient {
+    pub fn new(bind: impl ToSocketAddrs, server: impl ToSocketAddrs, role: Option<Role>) -> io::Result<Self> {
+        let socket = UdpSocket::bind(bind)?;
+        socket.set_nonblocking(true)?;
+        let server = server
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no server address"))?;
+
+        Ok(Self {
+            socket,
+            server,
+            requested: role,
+            role: None,
+            rejected: false,
+            sequence: 0,
+            pending: VecDeque::new(),
+            snapshot: None,
+            recording: None,
+            accumulator: 0.0,
+            last_sent: None,
+            last_received: None,
+        })
+    }
+
+    /// Place attribuée par le serveur, `None` tant qu'il n'a pas répondu
+    pub fn role(&self) -> Option<Role> {
+        self.role
+    }
+
+    /// Le serveur n'avait plus de place pour le rôle demandé
+    pub fn is_rejected(&self) -> bool {
+        self.rejected
+    }
+
+    /// Dernier état reçu du serveur (score, phase de jeu...)
+    pub fn snapshot(&self) -> Option<&Snapshot> {
+        self.snapshot.as_ref()
+    }
+
+    pub fn server_silence(&self) -> Option<Duration> {
+        self.last_received.map(|at| at.elapsed())
+    }
+
+    /// Nombre d'entrées pas encore confirmées par le serveur
+    pub fn pending_inputs(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Garde chaque instantané reçu pour l'enregistrer ensuite comme un flux spectateur
+    /// Enregistre les images reçues ; `tick_rate` est remplacé par celui du serveur à son accueil
+    pub fn start_recording(&mut self, tick_rate: f32) {
+        self.recording = Some(StreamRecording::new(tick_rate));
+    }
+
+    pub fn stop_recording(&mut self) -> Option<StreamRecording> {
+        self.recording.take()
+    }
+
+    pub fn leave(&mut self) {
+        self.send(&Message::Leave);
+        self.role = None;
+    }
+
+    /// Traite les messages du serveur et recale `engine` sur le dernier instantané
+    pub fn poll(&mut self, engine: &mut Engine) {
+        let mut buffer = [0u8; 1500];
+        loop {
+            let len = match self.socket.recv_from(&mut buffer) {
+                Ok((len, from)) if from == self.server => len,
+                Ok(_) => continue,
+                Err(e) if e.kind() == io::ErrorKind::ConnectionReset => continue,
+                Err(_) => break,
+            };
+            let Some(message) = Message::decode(&buffer[..len]) else {
+                continue;
+            };
+            self.last_received = Some(Instant::now());
+
+            match message {
+                Message::Welcome { role, tick_rate } => {
+                    self.role = Some(role);
+                    engine.tick_rate = tick_rate;
+                    if let Some(recording) = &mut self.recording {
+                        recording.tick_rate = tick_rate;
+                    }
+                }
+                Message::Rejected => self.rejected = true,
+                Message::Snapshot { ack, snapshot } => self.reconcile(engine, ack, snapshot),
+                // Image de référence perdue ou dans le désordre : on demande une image complète
+                Message::SpectatorFrame(frame) => match frame.apply(self.snapshot.as_ref()) {
+                    Some(snapshot) => self.reconcile(engine, 0, snapshot),
+                    None if self.snapshot.is_none_or(|last| last.tick < frame.tick) => self.send(&Message::Resync),
+                    None => {}
+                },
+                _ => {}
+            }
+        }
+
+        // Demande de connexion répétée tant que le serveur n'a pas répondu, signe de vie pour un spectateur
+        let keep_alive = match self.role {
+            None => (!self.rejected).then_some(Message::Join { role: self.requested }),
+            Some(Role::Spectator) => Some(Message::Heartbeat),
+            Some(Role::Player(_)) => None,
+        };
+        if let Some(message) = keep_alive
+            && self.last_sent.is_none_or(|at| at.elapsed() >= JOIN_RETRY)
+        {
+            self.send(&message);
+        }
+    }
+
+    /// Avance l'affichage au rythme du serveur et envoie l'entrée locale ; renvoie le nombre de pas
+    pub fn update(&mut self, engine: &mut Engine, frame_time: Duration, input: NetInput) -> u32 {
+        self.poll(engine);
+
+        let dt = engine.fixed_dt();
+        self.accumulator += frame_time.as_secs_f32();
+
+        let mut input = input;
+        let mut steps = 0;
+        while self.accumulator >= dt && steps < engine.max_steps_per_frame {
+            self.predict(engine, input, dt);
+            self.accumulator -= dt;
+            input = input.predicted();
+            steps += 1;
+        }
+        self.accumulator = self.accumulator.min(dt * engine.max_steps_per_frame as f32);
+
+        steps
+    }
+
+    fn send(&mut self, message: &Message) {
+        if let Err(e) = self.socket.send_to(&message.encode(), self.server) {
+            log::debug!("send to {} failed: {}", self.server, e);
+        }
+        self.last_sent = Some(Instant::now());
+    }
+
+    // Un pas côté client : sa raquette suit l'entrée locale, le reste avance en ligne droite
+    fn predict(&mut self, engine: &mut Engine, input: NetInput, dt: f32) {
+        if let Some(Role::Player(player)) = self.role {
+            self.sequence = self.sequence.wrapping_add(1);
+            self.pending.push_back((self.sequence, input));
+            self.send(&Message::Input {
+                sequence: self.sequence,
+                input,
+            });
+
+            if self.in_play() {
+                engine.physics.set_paddle_input(player, input.paddle);
+                engine.physics.update_paddle(player, dt);
+            }
+        }
+
+        if self.snapshot.is_some_and(|snapshot| snapshot.state == GameState::Rally) {
+            let scene = &mut engine.physics.scene;
+            let mut handles = vec![scene.ball];
+            if let Some(Role::Player(player)) = self.role {
+                handles.push(scene.paddle(player.opponent()));
+            } else {
+                handles.extend([scene.player1, scene.player2]);
+            }
+
+            for handle in handles {
+                let component = scene.component_mut(handle);
+                component.position += component.velocity * dt;
+            }
+        }
+        engine.reset_interpolation();
+    }
+
+    fn in_play(&self) -> bool {
+        self.snapshot.is_some_and(|snapshot| snapshot.state.is_in_play())
+    }
+
+    fn reconcile(&mut self, engine: &mut Engine, ack: u32, snapshot: Snapshot) {
+        // Les datagrammes peuvent arriver dans le désordre
+        if self.snapshot.is_some_and(|last| last.tick >= snapshot.tick) {
+            return;
+        }
+        self.snapshot = Some(snapshot);
+        snapshot.write_to(&mut engine.physics.scene);
+        engine.reset_interpolation();
+        if let Some(recording) = &mut self.recording {
+            recording.push(snapshot);
+        }
+
+        let Some(Role::Player(player)) = self.role else {
+            return;
+        };
+
+        while self.pending.front().is_some_and(|(sequence, _)| *sequence <= ack) {
+            self.pending.pop_front();
+        }
+
+        // La raquette du serveur a du retard sur nos entrées : on rejoue celles qu'il n'a pas vues
+        if self.in_play() {
+            let dt = engine.fixed_dt();
+            for (_, input) in &self.pending {
+                engine.physics.set_paddle_input(player, input.paddle);
+                engine.physics.update_paddle(player, dt);
+            }
+            engine.reset_interpolation();
+        }
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(feature = "render")]
+use std::{cell::RefCell, rc::Rc};
+use crate::engine::{GameRng, Scene};
+#[cfg(feature = "render")]
+use crate::input::{Action, InputState};
+use crate::scoring::Player;
+
+/// Source des déplacements d'une raquette : clavier, IA, replay, réseau...
+pub trait PaddleController {
+    /// Direction voulue pour la raquette de `player` à ce pas, entre -1.0 (bas) et 1.0 (haut).
+    /// Tout tirage aléatoire doit passer par `rng`, le générateur du moteur.
+    fn intent(&mut self, player: Player, scene: &Scene, rng: &mut GameRng, dt: f32) -> f32;
+}
+
+/// Raquette pilotée par les actions maintenues au clavier
+#[cfg(feature = "render")]
+pub struct KeyboardController {
+    input: Rc<RefCell<InputState>>,
+    up: Action,
+    down: Action,
+}
+
+#[cfg(feature = "render")]
+impl KeyboardController {
+    pub fn new(input: Rc<RefCell<InputState>>, up: Action, down: Action) -> Self {
+        Self { input, up, down }
+    }
+
+    /// Contrôles par défaut du joueur : W/S pour la gauche, flèches pour la droite
+    pub fn for_player(input: Rc<RefCell<InputState>>, player: Player) -> Self {
+        match player {
+            Player::One => Self::new(input, Action::P1Up, Action::P1Down),
+            Player::Two => Self::new(input, Action::P2Up, Action::P2Down),
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+impl PaddleController for KeyboardController {
+    fn intent(&mut self, _player: Player, _scene: &Scene, _rng: &mut GameRng, _dt: f32) -> f32 {
+        self.input.borrow().axis(self.up, self.down)
+    }
+}
@@ -1,59 +1,608 @@
-use glam::{vec2};
+use std::f32::consts::FRAC_PI_4;
+use std::time::Duration;
+use glam::{vec2, Vec2};
+use rand::rngs::Xoshiro256PlusPlus;
+use rand::{RngExt, SeedableRng};
+use crate::controller::PaddleController;
 use crate::rigid_body::{Component, RigidBody};
-use crate::physics::Physics;
+use crate::physics::{Deflection, PaddleMotion, Physics, RallySpeed};
+use crate::physics_world::{Body, BodyHandle, BodyType, PhysicsWorld};
+use crate::events::{CollisionEvent, EventQueue};
+use crate::game_state::{GameEvent, GameFlow, GameState};
+use crate::replay::{Playback, Replay};
+use crate::scoring::{MatchRules, Player, Scoring};
 
+/// Générateur du jeu : copiable pour le rollback et identique sur toutes les plateformes
+pub type GameRng = Xoshiro256PlusPlus;
+
+const SERVE_SPEED: f32 = 0.75;
+/// Angle maximal du service par rapport à l'horizontale
+const SERVE_MAX_ANGLE: f32 = FRAC_PI_4;
+
+#[derive(Clone)]
 pub struct Scene {
     pub size: (u32, u32),
-    pub ball: Component,
-    pub player1: Component,
-    pub player2: Component,
-    pub ball_body: RigidBody,
+    pub world: PhysicsWorld,
+    pub ball: BodyHandle,
+    pub player1: BodyHandle,
+    pub player2: BodyHandle,
+    pub top_wall: BodyHandle,
+    pub bottom_wall: BodyHandle,
+}
+
+impl Scene {
+    pub fn component(&self, handle: BodyHandle) -> &Component {
+        &self.world[handle].component
+    }
+
+    pub fn component_mut(&mut self, handle: BodyHandle) -> &mut Component {
+        &mut self.world[handle].component
+    }
+
+    pub fn paddle(&self, player: Player) -> BodyHandle {
+        match player {
+            Player::One => self.player1,
+            Player::Two => self.player2,
+        }
+    }
+
+    /// Empreinte des positions et vitesses de la balle et des raquettes, bit à bit
+    pub fn checksum(&self) -> u64 {
+        // FNV-1a : stable d'une version de Rust à l'autre, contrairement à `DefaultHasher`
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for handle in [self.ball, self.player1, self.player2] {
+            let component = self.component(handle);
+            for value in [component.position, component.velocity] {
+                for byte in [value.x, value.y].iter().flat_map(|v| v.to_bits().to_le_bytes()) {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+                }
+            }
+        }
+        hash
+    }
+
+    pub fn positions(&self) -> ScenePositions {
+        ScenePositions {
+            ball: self.component(self.ball).position,
+            player1: self.component(self.player1).position,
+            player2: self.component(self.player2).position,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ScenePositions {
+    pub ball: Vec2,
+    pub player1: Vec2,
+    pub player2: Vec2,
+}
+
+impl ScenePositions {
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            ball: self.ball.lerp(other.ball, t),
+            player1: self.player1.lerp(other.player1, t),
+            player2: self.player2.lerp(other.player2, t),
+        }
+    }
+}
+
+/// Copie de tout ce qui fait évoluer la simulation, pour revenir en arrière (rollback réseau)
+#[derive(Clone)]
+pub struct EngineState {
+    scene: Scene,
+    rally: RallySpeed,
+    paddle_input: [f32; 2],
+    scoring: Scoring,
+    flow: GameFlow,
+    previous: ScenePositions,
+    tick: u64,
+    rng: GameRng,
+    controller_rng: GameRng,
 }
 
 pub struct Engine {
     pub physics: Physics,
-    dt: f32,
+    pub scoring: Scoring,
+    pub flow: GameFlow,
+    // Une raquette sans contrôleur garde la direction fixée via `Physics::set_paddle_input`
+    controllers: [Option<Box<dyn PaddleController>>; 2],
+    /// Événements de collision en attente, à vider par les consommateurs à chaque frame
+    pub events: EventQueue,
+    /// Fréquence de la simulation, en pas par seconde
+    pub tick_rate: f32,
+    /// Nombre maximal de pas rattrapés par frame
+    pub max_steps_per_frame: u32,
+    accumulator: f32,
+    previous: ScenePositions,
+    tick: u64,
+    // Unique source d'aléatoire du jeu : même graine et mêmes entrées, même partie
+    seed: u64,
+    rng: GameRng,
+    // Flux séparé pour les contrôleurs : un replay ne les exécute pas, les services doivent rester identiques
+    controller_rng: GameRng,
+    recording: Option<Replay>,
+    playback: Option<Playback>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Engine {
     pub fn new() -> Self {
+        Self::with_rules(MatchRules::default())
+    }
+
+    pub fn with_rules(rules: MatchRules) -> Self {
+        Self::with_seed(rules, rand::random())
+    }
+
+    pub fn with_seed(rules: MatchRules, seed: u64) -> Self {
+        let (rng, controller_rng) = seeded_rngs(seed);
+        let mut world = PhysicsWorld::new();
+
+        let ball = world.insert(
+            Body::new(
+                Component::new(vec2(0.0, 0.0), vec2(0.02, 0.02), [1.0, 0.0, 0.0]),
+                BodyType::Dynamic,
+            )
+            .with_rigid_body(RigidBody::new(1.0, 1.0)),
+        );
+
+        let player1 = world.insert(Body::new(
+            Component::new(vec2(-0.85, 0.0), vec2(0.03, 0.2), [1.0, 1.0, 1.0]),
+            BodyType::Kinematic,
+        ));
+
+        let player2 = world.insert(Body::new(
+            Component::new(vec2(0.85, 0.0), vec2(0.03, 0.2), [1.0, 1.0, 1.0]),
+            BodyType::Kinematic,
+        ));
+
+        // Murs haut et bas, juste hors de l'écran
+        let top_wall = world.insert(Body::new(
+            Component::new(vec2(0.0, 1.5), vec2(2.0, 0.5), [0.0, 0.0, 0.0]),
+            BodyType::Static,
+        ));
+
+        let bottom_wall = world.insert(Body::new(
+            Component::new(vec2(0.0, -1.5), vec2(2.0, 0.5), [0.0, 0.0, 0.0]),
+            BodyType::Static,
+        ));
+
         let scene = Scene {
             size: (800, 600),
-
-            ball: Component::new(
-                vec2(0.0, 0.0),
-                vec2(0.02, 0.02),
-                [1.0, 0.0, 0.0]
-            ),
-
-            player1: Component::new(
-                vec2(-0.85, 0.0),
-                vec2(0.03, 0.2),
-                [1.0, 1.0, 1.0]
-            ),
-
-            player2: Component::new(
-                vec2(0.85, 0.0),
-                vec2(0.03, 0.2),
-                [1.0, 1.0, 1.0]
-            ),
-
-            ball_body: RigidBody {
-                mass: 1.0,
-                restitution: 1.0,
-            },
+            world,
+            ball,
+            player1,
+            player2,
+            top_wall,
+            bottom_wall,
         };
 
         Self {
-            dt: 0.016,
+            previous: scene.positions(),
             physics: Physics {
                 gravity: 0.0,
                 scene,
+                deflection: Deflection::default(),
+                rally: RallySpeed::default(),
+                paddle_motion: PaddleMotion::default(),
+                paddle_input: [0.0; 2],
+                events: Vec::new(),
             },
+            events: EventQueue::new(),
+            scoring: Scoring::new(rules),
+            flow: GameFlow::new(),
+            controllers: [None, None],
+            tick_rate: 60.0,
+            max_steps_per_frame: 5,
+            accumulator: 0.0,
+            tick: 0,
+            seed,
+            rng,
+            controller_rng,
+            recording: None,
+            playback: None,
+        }
+    }
+
+    /// Moteur qui rejoue `replay` : entrées et événements viennent du fichier, plus des contrôleurs
+    pub fn from_replay(replay: &Replay) -> Self {
+        let mut engine = Self::with_seed(replay.rules, replay.seed);
+        engine.tick_rate = replay.tick_rate;
+        engine.playback = Some(Playback::new(replay.clone()));
+        engine
+    }
+
+    /// Graine du générateur, à enregistrer pour rejouer la partie
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Repart de `seed` pour tous les tirages suivants
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        (self.rng, self.controller_rng) = seeded_rngs(seed);
+    }
+
+    pub fn save_state(&self) -> EngineState {
+        EngineState {
+            scene: self.physics.scene.clone(),
+            rally: self.physics.rally,
+            paddle_input: self.physics.paddle_input,
+            scoring: self.scoring.clone(),
+            flow: self.flow.clone(),
+            previous: self.previous,
+            tick: self.tick,
+            rng: self.rng.clone(),
+            controller_rng: self.controller_rng.clone(),
+        }
+    }
+
+    /// Revient à un état sauvegardé ; contrôleurs, enregistrement et événements en attente sont conservés
+    pub fn load_state(&mut self, state: &EngineState) {
+        self.physics.scene = state.scene.clone();
+        self.physics.rally = state.rally;
+        self.physics.paddle_input = state.paddle_input;
+        self.scoring = state.scoring.clone();
+        self.flow = state.flow.clone();
+        self.previous = state.previous;
+        self.tick = state.tick;
+        self.rng = state.rng.clone();
+        self.controller_rng = state.controller_rng.clone();
+        self.accumulator = 0.0;
+    }
+
+    /// Enregistre la partie pour la rejouer ; à appeler avant le premier pas
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Replay::new(self.scoring.rules, self.seed, self.tick_rate));
+    }
+
+    pub fn recording(&self) -> Option<&Replay> {
+        self.recording.as_ref()
+    }
+
+    pub fn stop_recording(&mut self) -> Option<Replay> {
+        self.recording.take()
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// Vrai quand un replay a été joué jusqu'à son dernier pas
+    pub fn replay_finished(&self) -> bool {
+        self.playback.as_ref().is_some_and(|playback| playback.is_finished(self.tick))
+    }
+
+    pub fn rng(&mut self) -> &mut GameRng {
+        &mut self.rng
+    }
+
+    pub fn fixed_dt(&self) -> f32 {
+        1.0 / self.tick_rate
+    }
+
+    /// Fraction du pas en cours écoulée depuis le dernier pas de simulation
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.fixed_dt()).clamp(0.0, 1.0)
+    }
+
+    /// Positions à afficher, interpolées entre les deux derniers pas
+    pub fn interpolated_positions(&self) -> ScenePositions {
+        self.previous.lerp(&self.physics.scene.positions(), self.alpha())
+    }
+
+    /// Affiche la scène telle quelle, sans interpoler depuis le pas précédent (scène modifiée de l'extérieur)
+    pub fn reset_interpolation(&mut self) {
+        self.previous = self.physics.scene.positions();
+    }
+
+    pub fn set_controller(&mut self, player: Player, controller: Box<dyn PaddleController>) {
+        self.controllers[player.index()] = Some(controller);
+    }
+
+    pub fn clear_controller(&mut self, player: Player) -> Option<Box<dyn PaddleController>> {
+        self.physics.set_paddle_input(player, 0.0);
+        self.controllers[player.index()].take()
+    }
+
+    pub fn state(&self) -> GameState {
+        self.flow.state()
+    }
+
+    /// Événement extérieur (joueur, réseau...) ; ignoré pendant un replay, qui fournit les siens
+    pub fn handle_event(&mut self, event: GameEvent) {
+        if self.playback.is_some() {
+            return;
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.record_event(self.tick, event);
+        }
+        self.transition(event);
+    }
+
+    fn transition(&mut self, event: GameEvent) {
+        let previous = self.flow.state();
+        if let Some(next) = self.flow.handle(event) {
+            // Une reprise retrouve l'état mis en pause tel quel, sans ses effets d'entrée
+            let resumed = previous == GameState::Paused && event == GameEvent::TogglePause;
+            if !resumed {
+                self.enter(previous, next);
+            }
+        }
+    }
+
+    pub fn update(&mut self, frame_time: Duration) {
+        let dt = self.fixed_dt();
+        self.accumulator += frame_time.as_secs_f32();
+
+        let mut steps = 0;
+        while self.accumulator >= dt {
+            if steps == self.max_steps_per_frame {
+                // Trop de retard : on abandonne le temps restant plutôt que de spiraler
+                self.accumulator %= dt;
+                break;
+            }
+
+            self.step(dt);
+            self.accumulator -= dt;
+            steps += 1;
         }
     }
 
-    pub fn update(&mut self) {
-        self.physics.update(self.dt);
+    /// Nombre de pas simulés depuis la création du moteur
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn step(&mut self, dt: f32) {
+        if let Some(playback) = &mut self.playback {
+            for event in playback.events_before(self.tick) {
+                self.transition(event);
+            }
+            if self.replay_finished() {
+                return;
+            }
+        }
+
+        self.simulate(dt);
+
+        if let Some(recording) = &mut self.recording {
+            let checksum = self.physics.scene.checksum();
+            recording.record_tick(self.physics.paddle_input, checksum);
+        }
     }
-}
\ No newline at end of file
+
+    fn simulate(&mut self, dt: f32) {
+        self.tick += 1;
+        self.previous = self.physics.scene.positions();
+
+        let previous = self.flow.state();
+        if let Some(next) = self.flow.tick(dt) {
+            self.enter(previous, next);
+        }
+
+        let in_play = self.flow.state().is_in_play();
+
+        if let Some(playback) = &self.playback {
+            if let Some(input) = playback.input(self.tick) {
+                self.physics.paddle_input = input;
+            }
+        } else if in_play {
+            for player in [Player::One, Player::Two] {
+                if let Some(controller) = &mut self.controllers[player.index()] {
+                    let intent = controller.intent(player, &self.physics.scene, &mut self.controller_rng, dt);
+                    self.physics.set_paddle_input(player, intent);
+                }
+            }
+        }
+
+        match self.flow.state() {
+            GameState::Rally => {}
+            // Hors échange, seules les raquettes bougent
+            GameState::ServeCountdown { .. } | GameState::PointScored { .. } => {
+                self.physics.update_kinematic(dt);
+                return;
+            }
+            _ => return,
+        }
+
+        self.physics.update(dt);
+        self.events.extend(self.physics.events.iter().copied());
+
+        let scorer = self.physics.events.iter().find_map(|event| match event {
+            CollisionEvent::BallLeftArena { side } => Some(side.defender().opponent()),
+            _ => None,
+        });
+
+        if let Some(scorer) = scorer {
+            let event = match self.scoring.award_point(scorer) {
+                Some(result) => GameEvent::MatchOver(result),
+                None => GameEvent::BallOut(scorer),
+            };
+            self.transition(event);
+        }
+    }
+
+    // Effets de bord à l'entrée d'un état (la mise en pause n'en a aucun)
+    fn enter(&mut self, previous: GameState, next: GameState) {
+        match next {
+            GameState::ServeCountdown { .. } => {
+                if matches!(previous, GameState::Title | GameState::GameOver { .. }) {
+                    self.scoring.reset();
+                }
+                self.center_ball();
+            }
+            GameState::Rally => self.serve(),
+            GameState::Title | GameState::PointScored { .. } | GameState::GameOver { .. } => {
+                self.center_ball();
+            }
+            GameState::Paused => {}
+        }
+    }
+
+    fn center_ball(&mut self) {
+        let scene = &mut self.physics.scene;
+        let ball = scene.component_mut(scene.ball);
+        ball.position = vec2(0.0, 0.0);
+        ball.velocity = vec2(0.0, 0.0);
+        self.previous = self.physics.scene.positions();
+    }
+
+    // Lance la balle depuis le centre vers l'adversaire du serveur, avec un angle tiré au hasard
+    fn serve(&mut self) {
+        let direction = match self.scoring.server() {
+            Player::One => 1.0,
+            Player::Two => -1.0,
+        };
+        let angle = self.rng.random_range(-SERVE_MAX_ANGLE..=SERVE_MAX_ANGLE);
+
+        let scene = &mut self.physics.scene;
+        let ball = scene.component_mut(scene.ball);
+        ball.position = vec2(0.0, 0.0);
+        // Trigonométrie de libm, identique sur toutes les plateformes (rollback, replays)
+        ball.velocity = vec2(direction * libm::cosf(angle), libm::sinf(angle)) * SERVE_SPEED;
+        self.physics.rally.reset();
+        self.previous = self.physics.scene.positions();
+    }
+}
+
+// Générateurs des services et des contrôleurs, tous deux dérivés de la graine du moteur
+fn seeded_rngs(seed: u64) -> (GameRng, GameRng) {
+    let mut rng = GameRng::seed_from_u64(seed);
+    let controller_rng = GameRng::from_rng(&mut rng);
+    (rng, controller_rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::GOAL_LINE;
+
+    fn ball(engine: &Engine) -> (Vec2, Vec2) {
+        let scene = &engine.physics.scene;
+        let ball = scene.component(scene.ball);
+        (ball.position, ball.velocity)
+    }
+
+    fn paused_rally() -> Engine {
+        let mut engine = Engine::with_seed(MatchRules::default(), 1);
+        engine.handle_event(GameEvent::Start);
+        engine.handle_event(GameEvent::Serve);
+        for _ in 0..10 {
+            engine.step(engine.fixed_dt());
+        }
+        engine.handle_event(GameEvent::TogglePause);
+        assert_eq!(engine.state(), GameState::Paused);
+        engine
+    }
+
+    #[test]
+    fn resume_keeps_the_ball_in_flight() {
+        let mut engine = paused_rally();
+        let before = ball(&engine);
+
+        engine.handle_event(GameEvent::TogglePause);
+
+        assert_eq!(engine.state(), GameState::Rally);
+        assert_eq!(ball(&engine), before);
+    }
+
+    #[test]
+    fn restart_from_pause_centers_the_ball() {
+        let mut engine = paused_rally();
+        assert_ne!(ball(&engine).0, Vec2::ZERO);
+
+        engine.handle_event(GameEvent::Restart);
+
+        assert_eq!(engine.state(), GameState::Title);
+        assert_eq!(ball(&engine), (Vec2::ZERO, Vec2::ZERO));
+    }
+
+    // Place la balle derrière la ligne de but de `defender` et simule un pas
+    fn concede(engine: &mut Engine, defender: Player) {
+        let x = match defender {
+            Player::One => -GOAL_LINE - 0.1,
+            Player::Two => GOAL_LINE + 0.1,
+        };
+        let scene = &mut engine.physics.scene;
+        let ball = scene.component_mut(scene.ball);
+        ball.position = vec2(x, 0.0);
+        ball.velocity = Vec2::ZERO;
+        engine.step(engine.fixed_dt());
+    }
+
+    // Avance jusqu'à quitter l'état courant, en au plus `max` pas
+    fn step_until_change(engine: &mut Engine, max: usize) -> GameState {
+        let start = std::mem::discriminant(&engine.state());
+        for _ in 0..max {
+            engine.step(engine.fixed_dt());
+            if std::mem::discriminant(&engine.state()) != start {
+                break;
+            }
+        }
+        engine.state()
+    }
+
+    #[test]
+    fn countdown_ends_with_a_serve() {
+        let mut engine = Engine::with_seed(MatchRules::default(), 1);
+        engine.handle_event(GameEvent::Start);
+        assert!(matches!(engine.state(), GameState::ServeCountdown { .. }));
+        assert_eq!(ball(&engine), (Vec2::ZERO, Vec2::ZERO));
+
+        assert_eq!(step_until_change(&mut engine, 200), GameState::Rally);
+        let (_, velocity) = ball(&engine);
+        // Le joueur 1 sert vers la droite
+        assert!(velocity.x > 0.0);
+    }
+
+    #[test]
+    fn point_scored_leads_back_to_the_serve() {
+        let mut engine = Engine::with_seed(MatchRules::default(), 1);
+        engine.handle_event(GameEvent::Start);
+        engine.handle_event(GameEvent::Serve);
+
+        concede(&mut engine, Player::Two);
+        assert!(matches!(
+            engine.state(),
+            GameState::PointScored { scorer: Player::One, .. }
+        ));
+        assert_eq!(engine.scoring.points(Player::One), 1);
+        assert_eq!(ball(&engine), (Vec2::ZERO, Vec2::ZERO));
+
+        let state = step_until_change(&mut engine, 200);
+        assert!(matches!(state, GameState::ServeCountdown { .. }));
+        // Le score est conservé d'un service à l'autre
+        assert_eq!(engine.scoring.points(Player::One), 1);
+    }
+
+    #[test]
+    fn last_point_ends_the_match() {
+        let rules = MatchRules {
+            points_to_win: 1,
+            win_by: 1,
+            ..MatchRules::default()
+        };
+        let mut engine = Engine::with_seed(rules, 1);
+        engine.handle_event(GameEvent::Start);
+        engine.handle_event(GameEvent::Serve);
+
+        concede(&mut engine, Player::One);
+
+        let GameState::GameOver { result } = engine.state() else {
+            panic!("expected game over, got {:?}", engine.state());
+        };
+        assert_eq!(result.winner, Player::Two);
+        // Un nouveau match repart de zéro
+        engine.handle_event(GameEvent::Start);
+        assert_eq!(engine.scoring.points(Player::Two), 0);
+        assert!(!engine.scoring.is_over());
+    }
+}
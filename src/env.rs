@@ -0,0 +1,199 @@
+use crate::ai::{AiController, AiDifficulty};
+use crate::events::CollisionEvent;
+use crate::game_state::{GameEvent, GameState};
+use crate::headless::{PaddleAction, Simulation};
+use crate::scoring::{MatchRules, Player};
+
+pub const OBSERVATION_SIZE: usize = 8;
+
+/// Vue de l'agent : balle (x, y, vx, vy), sa raquette (y, vy), raquette adverse (y, vy).
+/// Les abscisses sont retournées pour le joueur de droite : l'agent défend toujours le côté gauche.
+pub type Observation = [f32; OBSERVATION_SIZE];
+
+#[derive(Clone, Copy, Debug)]
+pub struct RewardConfig {
+    pub point_won: f32,
+    pub point_lost: f32,
+    /// Bonus quand l'agent renvoie la balle
+    pub ball_touched: f32,
+    /// Appliqué à chaque pas de simulation, pour encourager les échanges courts ou longs
+    pub per_tick: f32,
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self {
+            point_won: 1.0,
+            point_lost: -1.0,
+            ball_touched: 0.1,
+            per_tick: 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EnvConfig {
+    pub rules: MatchRules,
+    pub agent: Player,
+    /// Adversaire piloté par l'IA ; immobile si `None`
+    pub opponent: Option<AiDifficulty>,
+    pub rewards: RewardConfig,
+    /// Nombre de pas de simulation joués pour chaque action
+    pub frame_skip: u32,
+    /// Épisode tronqué au-delà de ce nombre de pas de simulation
+    pub max_ticks: Option<u64>,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            rules: MatchRules::default(),
+            agent: Player::One,
+            opponent: Some(AiDifficulty::normal()),
+            rewards: RewardConfig::default(),
+            frame_skip: 4,
+            max_ticks: Some(60 * 60 * 10),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StepInfo {
+    pub tick: u64,
+    pub points_won: u32,
+    pub points_lost: u32,
+    pub touches: u32,
+    /// Fin d'épisode due à `max_ticks` et non à la fin du match
+    pub truncated: bool,
+    pub winner: Option<Player>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StepResult {
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool,
+    pub info: StepInfo,
+}
+
+/// Environnement d'apprentissage par renforcement, façon gym : `reset` puis `step` jusqu'à `done`
+pub struct PongEnv {
+    pub config: EnvConfig,
+    simulation: Simulation,
+    done: bool,
+}
+
+impl PongEnv {
+    pub fn new(config: EnvConfig) -> Self {
+        let mut env = Self {
+            config,
+            simulation: Simulation::new(config.rules),
+            done: false,
+        };
+        env.reset(0);
+        env
+    }
+
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.simulation = Simulation::with_seed(self.config.rules, seed);
+        self.done = false;
+
+        if let Some(difficulty) = self.config.opponent {
+            self.simulation
+                .engine
+                .set_controller(self.config.agent.opponent(), Box::new(AiController::new(difficulty)));
+        }
+
+        self.simulation.send(GameEvent::Start);
+        self.observe()
+    }
+
+    pub fn step(&mut self, action: PaddleAction) -> StepResult {
+        let rewards = self.config.rewards;
+        let agent = self.config.agent;
+        let mut reward = 0.0;
+        let mut info = StepInfo::default();
+
+        if !self.done {
+            self.simulation.set_action(agent, action);
+
+            for _ in 0..self.config.frame_skip.max(1) {
+                self.simulation.step();
+                reward += rewards.per_tick;
+
+                for event in self.simulation.engine.events.drain() {
+                    match event {
+                        CollisionEvent::BallLeftArena { side } if side.defender() == agent => {
+                            info.points_lost += 1;
+                            reward += rewards.point_lost;
+                        }
+                        CollisionEvent::BallLeftArena { .. } => {
+                            info.points_won += 1;
+                            reward += rewards.point_won;
+                        }
+                        CollisionEvent::BallHitPaddle { paddle, .. } if paddle == agent => {
+                            info.touches += 1;
+                            reward += rewards.ball_touched;
+                        }
+                        _ => {}
+                    }
+                }
+
+                if let GameState::GameOver { result } = self.simulation.engine.state() {
+                    info.winner = Some(result.winner);
+                    self.done = true;
+                    break;
+                }
+
+                let tick = self.simulation.engine.tick();
+                if self.config.max_ticks.is_some_and(|max| tick >= max) {
+                    info.truncated = true;
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+
+        info.tick = self.simulation.engine.tick();
+
+        StepResult {
+            observation: self.observe(),
+            reward,
+            done: self.done,
+            info,
+        }
+    }
+
+    pub fn observe(&self) -> Observation {
+        let snapshot = self.simulation.snapshot();
+        let agent = self.config.agent;
+        let mirror = match agent {
+            Player::One => 1.0,
+            Player::Two => -1.0,
+        };
+
+        let own = agent.index();
+        let other = agent.opponent().index();
+
+        [
+            snapshot.ball_position.x * mirror,
+            snapshot.ball_position.y,
+            snapshot.ball_velocity.x * mirror,
+            snapshot.ball_velocity.y,
+            snapshot.paddle_positions[own].y,
+            snapshot.paddle_velocities[own].y,
+            snapshot.paddle_positions[other].y,
+            snapshot.paddle_velocities[other].y,
+        ]
+    }
+}
+
+impl Default for PongEnv {
+    fn default() -> Self {
+        Self::new(EnvConfig::default())
+    }
+}
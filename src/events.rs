@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+use crate::box_collider::Contact;
+use crate::physics_world::BodyHandle;
+use crate::scoring::Player;
+
+/// Au-delà, les événements les plus anciens non consommés sont perdus
+const MAX_QUEUED_EVENTS: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    /// Joueur qui défend ce côté de l'arène
+    pub fn defender(self) -> Player {
+        match self {
+            Side::Left => Player::One,
+            Side::Right => Player::Two,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WallSide {
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CollisionEvent {
+    BallHitPaddle { paddle: Player, contact: Contact },
+    BallHitWall { side: WallSide },
+    BallLeftArena { side: Side },
+    /// Tout autre choc entre deux corps du monde
+    BodiesCollided { body: BodyHandle, other: BodyHandle, contact: Contact },
+}
+
+pub struct EventQueue {
+    events: VecDeque<CollisionEvent>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, event: CollisionEvent) {
+        if self.events.len() == MAX_QUEUED_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    pub fn extend(&mut self, events: impl IntoIterator<Item = CollisionEvent>) {
+        for event in events {
+            self.push(event);
+        }
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = CollisionEvent> + '_ {
+        self.events.drain(..)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CollisionEvent> {
+        self.events.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::scoring::{MatchResult, Player};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameState {
+    Title,
+    ServeCountdown { remaining: f32 },
+    Rally,
+    PointScored { scorer: Player, remaining: f32 },
+    Paused,
+    GameOver { result: MatchResult },
+}
+
+impl GameState {
+    /// Partie en cours : les raquettes bougent et la pause est possible
+    pub fn is_in_play(self) -> bool {
+        matches!(
+            self,
+            GameState::ServeCountdown { .. } | GameState::Rally | GameState::PointScored { .. }
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    /// Lance un nouveau match depuis l'écran titre ou la fin de partie
+    Start,
+    /// Sert immédiatement sans attendre la fin du décompte
+    Serve,
+    TogglePause,
+    BallOut(Player),
+    MatchOver(MatchResult),
+    /// Retour à l'écran titre
+    Restart,
+}
+
+#[derive(Clone)]
+pub struct GameFlow {
+    state: GameState,
+    paused_from: Option<GameState>,
+    pub serve_delay: f32,
+    pub point_delay: f32,
+}
+
+impl GameFlow {
+    pub fn new() -> Self {
+        Self {
+            state: GameState::Title,
+            paused_from: None,
+            serve_delay: 1.0,
+            point_delay: 1.0,
+        }
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn is_rally(&self) -> bool {
+        self.state == GameState::Rally
+    }
+
+    /// Applique un événement ; renvoie le nouvel état s'il y a eu transition
+    pub fn handle(&mut self, event: GameEvent) -> Option<GameState> {
+        let next = match (self.state, event) {
+            (GameState::Title | GameState::GameOver { .. }, GameEvent::Start | GameEvent::Serve) => {
+                GameState::ServeCountdown { remaining: self.serve_delay }
+            }
+
+            (GameState::ServeCountdown { .. }, GameEvent::Serve) => GameState::Rally,
+
+            (GameState::Rally, GameEvent::BallOut(scorer)) => GameState::PointScored {
+                scorer,
+                remaining: self.point_delay,
+            },
+
+            (GameState::Rally, GameEvent::MatchOver(result)) => GameState::GameOver { result },
+
+            (
+                state @ (GameState::ServeCountdown { .. }
+                | GameState::Rally
+                | GameState::PointScored { .. }),
+                GameEvent::TogglePause,
+            ) => {
+                self.paused_from = Some(state);
+                GameState::Paused
+            }
+
+            (GameState::Paused, GameEvent::TogglePause) => {
+                self.paused_from.take().unwrap_or(GameState::Title)
+            }
+
+            (_, GameEvent::Restart) => {
+                self.paused_from = None;
+                GameState::Title
+            }
+
+            _ => return None,
+        };
+
+        self.state = next;
+        Some(next)
+    }
+
+    /// Fait avancer les décomptes ; renvoie le nouvel état s'il y a eu transition
+    pub fn tick(&mut self, dt: f32) -> Option<GameState> {
+        let next = match &mut self.state {
+            GameState::ServeCountdown { remaining } => {
+                *remaining -= dt;
+                if *remaining > 0.0 {
+                    return None;
+                }
+                GameState::Rally
+            }
+
+            GameState::PointScored { remaining, .. } => {
+                *remaining -= dt;
+                if *remaining > 0.0 {
+                    return None;
+                }
+                GameState::ServeCountdown { remaining: self.serve_delay }
+            }
+
+            _ => return None,
+        };
+
+        self.state = next;
+        Some(next)
+    }
+}
+
+impl Default for GameFlow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,133 @@
+use glam::Vec2;
+use crate::engine::{Engine, Scene};
+use crate::game_state::{GameEvent, GameState};
+use crate::scoring::{MatchResult, MatchRules, Player};
+
+/// Commande d'une raquette pour les pas suivants
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PaddleAction {
+    Up,
+    Down,
+    #[default]
+    Idle,
+}
+
+impl PaddleAction {
+    pub fn direction(self) -> f32 {
+        match self {
+            PaddleAction::Up => 1.0,
+            PaddleAction::Down => -1.0,
+            PaddleAction::Idle => 0.0,
+        }
+    }
+}
+
+/// État observable de la partie après un pas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Snapshot {
+    pub tick: u64,
+    pub state: GameState,
+    pub ball_position: Vec2,
+    pub ball_velocity: Vec2,
+    pub paddle_positions: [Vec2; 2],
+    pub paddle_velocities: [Vec2; 2],
+    pub points: [u32; 2],
+    pub sets: [u32; 2],
+    pub server: Player,
+    pub rally_hits: u32,
+}
+
+impl Snapshot {
+    pub fn of(engine: &Engine) -> Self {
+        let scene = &engine.physics.scene;
+        let ball = scene.component(scene.ball);
+        let paddles = [Player::One, Player::Two].map(|player| scene.component(scene.paddle(player)));
+
+        Snapshot {
+            tick: engine.tick(),
+            state: engine.state(),
+            ball_position: ball.position,
+            ball_velocity: ball.velocity,
+            paddle_positions: paddles.map(|paddle| paddle.position),
+            paddle_velocities: paddles.map(|paddle| paddle.velocity),
+            points: [engine.scoring.points(Player::One), engine.scoring.points(Player::Two)],
+            sets: [engine.scoring.sets(Player::One), engine.scoring.sets(Player::Two)],
+            server: engine.scoring.server(),
+            rally_hits: engine.physics.rally.hits(),
+        }
+    }
+
+    /// Place la balle et les raquettes de `scene` comme dans l'instantané
+    pub fn write_to(&self, scene: &mut Scene) {
+        let ball = scene.component_mut(scene.ball);
+        ball.position = self.ball_position;
+        ball.velocity = self.ball_velocity;
+
+        for player in [Player::One, Player::Two] {
+            let paddle = scene.component_mut(scene.paddle(player));
+            paddle.position = self.paddle_positions[player.index()];
+            paddle.velocity = self.paddle_velocities[player.index()];
+        }
+    }
+}
+
+/// Partie simulée sans fenêtre ni GPU, pas à pas
+pub struct Simulation {
+    pub engine: Engine,
+}
+
+impl Simulation {
+    pub fn new(rules: MatchRules) -> Self {
+        Self {
+            engine: Engine::with_rules(rules),
+        }
+    }
+
+    /// Simulation reproductible : même graine et mêmes actions, même partie
+    pub fn with_seed(rules: MatchRules, seed: u64) -> Self {
+        Self {
+            engine: Engine::with_seed(rules, seed),
+        }
+    }
+
+    pub fn set_action(&mut self, player: Player, action: PaddleAction) {
+        self.engine.physics.set_paddle_input(player, action.direction());
+    }
+
+    pub fn send(&mut self, event: GameEvent) {
+        self.engine.handle_event(event);
+    }
+
+    /// Avance d'un pas fixe de simulation
+    pub fn step(&mut self) {
+        let dt = self.engine.fixed_dt();
+        self.engine.step(dt);
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::of(&self.engine)
+    }
+
+    /// Joue un match complet en servant automatiquement ; `None` si `max_ticks` est atteint avant la fin
+    pub fn run_match(&mut self, max_ticks: u64) -> Option<MatchResult> {
+        self.send(GameEvent::Start);
+
+        for _ in 0..max_ticks {
+            if let GameState::GameOver { result } = self.engine.state() {
+                return Some(result);
+            }
+            self.step();
+        }
+
+        match self.engine.state() {
+            GameState::GameOver { result } => Some(result),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new(MatchRules::default())
+    }
+}
@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use winit::keyboard::KeyCode;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    P1Up,
+    P1Down,
+    P2Up,
+    P2Down,
+    Serve,
+    Pause,
+    Quit,
+    /// Avance d'un pas pendant la lecture d'un replay
+    ReplayStep,
+    ReplayBack,
+    ReplayForward,
+    ReplaySlower,
+    ReplayFaster,
+    /// Réassigne une touche : appuyer ensuite sur la touche à changer, puis sur la nouvelle
+    Rebind,
+}
+
+impl Action {
+    pub const ALL: [Action; 13] = [
+        Action::P1Up,
+        Action::P1Down,
+        Action::P2Up,
+        Action::P2Down,
+        Action::Serve,
+        Action::Pause,
+        Action::Quit,
+        Action::ReplayStep,
+        Action::ReplayBack,
+        Action::ReplayForward,
+        Action::ReplaySlower,
+        Action::ReplayFaster,
+        Action::Rebind,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::P1Up => "P1Up",
+            Action::P1Down => "P1Down",
+            Action::P2Up => "P2Up",
+            Action::P2Down => "P2Down",
+            Action::Serve => "Serve",
+            Action::Pause => "Pause",
+            Action::Quit => "Quit",
+            Action::ReplayStep => "ReplayStep",
+            Action::ReplayBack => "ReplayBack",
+            Action::ReplayForward => "ReplayForward",
+            Action::ReplaySlower => "ReplaySlower",
+            Action::ReplayFaster => "ReplayFaster",
+            Action::Rebind => "Rebind",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BindingError {
+    /// La touche est déjà associée à une autre action
+    Conflict { key: KeyCode, existing: Action, requested: Action },
+    UnknownAction { line: usize, name: String },
+    UnknownKey { line: usize, name: String },
+    Syntax { line: usize },
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingError::Conflict { key, existing, requested } => write!(
+                f,
+                "key {key:?} is already bound to {}, cannot bind it to {}",
+                existing.name(),
+                requested.name()
+            ),
+            BindingError::UnknownAction { line, name } => write!(f, "line {line}: unknown action `{name}`"),
+            BindingError::UnknownKey { line, name } => write!(f, "line {line}: unknown key `{name}`"),
+            BindingError::Syntax { line } => write!(f, "line {line}: expected `Action = Key`"),
+        }
+    }
+}
+
+impl std::error::Error for BindingError {}
+
+/// Touches reconnues dans les fichiers de configuration, nommées comme les variantes de `KeyCode`
+const KNOWN_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF,
+    KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL,
+    KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+    KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX,
+    KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+    KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4,
+    KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+    KeyCode::ArrowUp, KeyCode::ArrowDown, KeyCode::ArrowLeft, KeyCode::ArrowRight,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight,
+    KeyCode::AltLeft, KeyCode::AltRight,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+];
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    KNOWN_KEYS.iter().copied().find(|&key| key_name(key) == name)
+}
+
+/// Associe les touches physiques aux actions du jeu
+#[derive(Clone, Debug)]
+pub struct InputMap {
+    bindings: HashMap<KeyCode, Action>,
+}
+
+impl InputMap {
+    /// Table vide, sans aucune touche associée
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.bindings.get(&key).copied()
+    }
+
+    pub fn keys_for(&self, action: Action) -> Vec<KeyCode> {
+        let mut keys: Vec<KeyCode> = self
+            .bindings
+            .iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(key, _)| *key)
+            .collect();
+        keys.sort_by_key(|key| key_name(*key));
+        keys
+    }
+
+    /// Ajoute une touche pour l'action, sans retirer celles déjà associées
+    pub fn bind(&mut self, key: KeyCode, action: Action) -> Result<(), BindingError> {
+        match self.bindings.get(&key) {
+            Some(&existing) if existing != action => Err(BindingError::Conflict {
+                key,
+                existing,
+                requested: action,
+            }),
+            _ => {
+                self.bindings.insert(key, action);
+                Ok(())
+            }
+        }
+    }
+
+    /// Remplace toutes les touches de l'action par `key`
+    pub fn rebind(&mut self, action: Action, key: KeyCode) -> Result<(), BindingError> {
+        match self.bindings.get(&key) {
+            Some(&existing) if existing != action => Err(BindingError::Conflict {
+                key,
+                existing,
+                requested: action,
+            }),
+            _ => {
+                self.bindings.retain(|_, bound| *bound != action);
+                self.bindings.insert(key, action);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn unbind(&mut self, key: KeyCode) -> Option<Action> {
+        self.bindings.remove(&key)
+    }
+
+    /// Lit une configuration `Action = Touche`, une association par ligne, `#` pour les commentaires
+    pub fn from_config(text: &str) -> Result<Self, BindingError> {
+        let mut map = Self::empty();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (action, key) = line
+                .split_once('=')
+                .ok_or(BindingError::Syntax { line: line_number })?;
+            let (action, key) = (action.trim(), key.trim());
+
+            let action = Action::from_name(action).ok_or_else(|| BindingError::UnknownAction {
+                line: line_number,
+                name: action.to_string(),
+            })?;
+            let key = key_from_name(key).ok_or_else(|| BindingError::UnknownKey {
+                line: line_number,
+                name: key.to_string(),
+            })?;
+
+            map.bind(key, action)?;
+        }
+
+        Ok(map)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Self::from_config(&text)?)
+    }
+
+    pub fn to_config(&self) -> String {
+        let mut text = String::new();
+        for action in Action::ALL {
+            for key in self.keys_for(action) {
+                text.push_str(&format!("{} = {}\n", action.name(), key_name(key)));
+            }
+        }
+        text
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_config())?;
+        Ok(())
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        let mut map = Self::empty();
+        for (key, action) in [
+            (KeyCode::KeyW, Action::P1Up),
+            (KeyCode::KeyS, Action::P1Down),
+            (KeyCode::ArrowUp, Action::P2Up),
+            (KeyCode::ArrowDown, Action::P2Down),
+            (KeyCode::Space, Action::Serve),
+            (KeyCode::KeyP, Action::Pause),
+            (KeyCode::Escape, Action::Quit),
+            (KeyCode::KeyN, Action::ReplayStep),
+            (KeyCode::ArrowLeft, Action::ReplayBack),
+            (KeyCode::ArrowRight, Action::ReplayForward),
+            (KeyCode::KeyJ, Action::ReplaySlower),
+            (KeyCode::KeyL, Action::ReplayFaster),
+            (KeyCode::F1, Action::Rebind),
+        ] {
+            map.bindings.insert(key, action);
+        }
+        map
+    }
+}
+
+/// Réassignation en cours, pilotée par les appuis suivant l'action `Rebind`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rebinding {
+    /// Attend une touche déjà associée, pour savoir quelle action changer
+    #[default]
+    SelectAction,
+    /// Attend la nouvelle touche de l'action
+    AwaitKey(Action),
+}
+
+impl Rebinding {
+    /// Traite un appui ; renvoie l'action une fois sa nouvelle touche associée.
+    /// En cas de conflit, la table est inchangée et on attend une autre touche.
+    pub fn press(&mut self, map: &mut InputMap, key: KeyCode) -> Result<Option<Action>, BindingError> {
+        match *self {
+            Rebinding::SelectAction => {
+                if let Some(action) = map.action(key) {
+                    *self = Rebinding::AwaitKey(action);
+                }
+                Ok(None)
+            }
+            Rebinding::AwaitKey(action) => {
+                map.rebind(action, key)?;
+                Ok(Some(action))
+            }
+        }
+    }
+}
+
+/// Touches maintenues et l'action associée au moment de l'appui
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    held: HashMap<KeyCode, Action>,
+}
+
+impl InputState {
+    pub fn press(&mut self, key: KeyCode, action: Action) {
+        self.held.insert(key, action);
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.held.remove(&key);
+    }
+
+    pub fn is_held(&self, action: Action) -> bool {
+        self.held.values().any(|&held| held == action)
+    }
+
+    /// 1.0 si seule `up` est maintenue, -1.0 si seule `down` l'est, 0.0 sinon
+    pub fn axis(&self, up: Action, down: Action) -> f32 {
+        let value = |action| if self.is_held(action) { 1.0 } else { 0.0 };
+        value(up) - value(down)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_is_parsed_with_comments_and_blank_lines() {
+        let text = "# Joueur 1\nP1Up = KeyW\n\n  P1Down=KeyS  # en bas\nP1Up = ArrowUp\n";
+        let map = InputMap::from_config(text).unwrap();
+
+        assert_eq!(map.keys_for(Action::P1Up), [KeyCode::ArrowUp, KeyCode::KeyW]);
+        assert_eq!(map.action(KeyCode::KeyS), Some(Action::P1Down));
+        assert_eq!(map.action(KeyCode::Space), None);
+    }
+
+    #[test]
+    fn invalid_config_reports_the_line() {
+        let cases = [
+            ("P1Up = KeyW\nP1Up KeyS\n", BindingError::Syntax { line: 2 }),
+            (
+                "Jump = Space\n",
+                BindingError::UnknownAction { line: 1, name: "Jump".to_string() },
+            ),
+            (
+                "\nServe = Spacebar\n",
+                BindingError::UnknownKey { line: 2, name: "Spacebar".to_string() },
+            ),
+            (
+                "P1Up = KeyW\nP2Up = KeyW\n",
+                BindingError::Conflict { key: KeyCode::KeyW, existing: Action::P1Up, requested: Action::P2Up },
+            ),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(InputMap::from_config(text).unwrap_err(), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn config_round_trips() {
+        let mut map = InputMap::default();
+        map.bind(KeyCode::Numpad8, Action::P2Up).unwrap();
+
+        let reloaded = InputMap::from_config(&map.to_config()).unwrap();
+        for action in Action::ALL {
+            assert_eq!(reloaded.keys_for(action), map.keys_for(action), "{}", action.name());
+        }
+    }
+
+    #[test]
+    fn rebind_replaces_every_key_of_the_action() {
+        let mut map = InputMap::default();
+        map.bind(KeyCode::KeyZ, Action::P1Up).unwrap();
+
+        map.rebind(Action::P1Up, KeyCode::KeyI).unwrap();
+
+        assert_eq!(map.keys_for(Action::P1Up), [KeyCode::KeyI]);
+        assert_eq!(map.action(KeyCode::KeyW), None);
+        assert_eq!(map.action(KeyCode::KeyZ), None);
+    }
+
+    #[test]
+    fn rebind_rejects_a_key_of_another_action() {
+        let mut map = InputMap::default();
+        let err = map.rebind(Action::P1Up, KeyCode::ArrowUp).unwrap_err();
+
+        assert_eq!(
+            err,
+            BindingError::Conflict { key: KeyCode::ArrowUp, existing: Action::P2Up, requested: Action::P1Up }
+        );
+        // La table est inchangée
+        assert_eq!(map.keys_for(Action::P1Up), [KeyCode::KeyW]);
+        assert_eq!(map.action(KeyCode::ArrowUp), Some(Action::P2Up));
+    }
+
+    #[test]
+    fn rebinding_selects_the_action_then_its_new_key() {
+        let mut map = InputMap::default();
+        let mut rebinding = Rebinding::default();
+
+        // Une touche libre ne sélectionne rien
+        assert_eq!(rebinding.press(&mut map, KeyCode::KeyQ), Ok(None));
+        assert_eq!(rebinding, Rebinding::SelectAction);
+
+        assert_eq!(rebinding.press(&mut map, KeyCode::KeyS), Ok(None));
+        assert_eq!(rebinding, Rebinding::AwaitKey(Action::P1Down));
+
+        // En conflit, on attend une autre touche
+        assert!(rebinding.press(&mut map, KeyCode::KeyW).is_err());
+        assert_eq!(rebinding, Rebinding::AwaitKey(Action::P1Down));
+
+        assert_eq!(rebinding.press(&mut map, KeyCode::KeyX), Ok(Some(Action::P1Down)));
+        assert_eq!(map.keys_for(Action::P1Down), [KeyCode::KeyX]);
+    }
+}
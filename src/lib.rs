@@ -0,0 +1,28 @@
+//! Pong en Rust : moteur de jeu et rendu logiciel utilisables sans fenêtre ni GPU,
+//! fenêtre et rendu WGPU avec la feature `render`
+
+pub mod ai;
+pub mod box_collider;
+pub mod cli;
+pub mod client;
+pub mod controller;
+pub mod engine;
+pub mod env;
+pub mod events;
+pub mod game_state;
+pub mod headless;
+pub mod net;
+pub mod physics;
+pub mod physics_world;
+pub mod protocol;
+pub mod render_backend;
+pub mod replay;
+pub mod rigid_body;
+pub mod scoring;
+pub mod server;
+pub mod spectator;
+
+#[cfg(feature = "render")]
+pub mod app;
+#[cfg(feature = "render")]
+pub mod input;
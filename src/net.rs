@@ -0,0 +1,466 @@
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+use rand::{RngExt, SeedableRng};
+use crate::engine::{Engine, EngineState, GameRng};
+use crate::game_state::GameEvent;
+use crate::scoring::Player;
+
+const MAGIC: &[u8; 2] = b"RP";
+const PROTOCOL_VERSION: u8 = 1;
+pub(crate) const INPUT_SIZE: usize = 5;
+/// Entrées renvoyées au plus par paquet ; les plus anciennes non acquittées partent en premier
+const MAX_INPUTS_PER_PACKET: usize = 64;
+
+/// Entrée d'un joueur pour un pas de simulation, échangée avec le pair
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NetInput {
+    pub paddle: f32,
+    /// Appui sur Servir pendant ce pas
+    pub serve: bool,
+    /// Appui sur Pause pendant ce pas
+    pub pause: bool,
+}
+
+impl NetInput {
+    pub(crate) fn encode(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.paddle.to_bits().to_le_bytes());
+        bytes.push(self.serve as u8 | (self.pause as u8) << 1);
+    }
+
+    /// Une direction non finie (NaN, infini) venue du réseau est lue comme 0.0
+    pub(crate) fn decode(bytes: &[u8]) -> Self {
+        let flags = bytes[4];
+        let paddle = f32::from_bits(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+        Self {
+            paddle: if paddle.is_finite() { paddle } else { 0.0 },
+            serve: flags & 1 != 0,
+            pause: flags & 2 != 0,
+        }
+    }
+
+    // Sans nouvelle du pair, on suppose qu'il garde sa direction et n'appuie sur rien
+    pub(crate) fn predicted(self) -> Self {
+        Self {
+            paddle: self.paddle,
+            ..Self::default()
+        }
+    }
+}
+
+/// Dégradation artificielle du réseau, pour tester en local
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NetConditions {
+    /// Délai ajouté à chaque paquet envoyé
+    pub latency: Duration,
+    /// Variation aléatoire maximale ajoutée au délai
+    pub jitter: Duration,
+    /// Probabilité de perdre un paquet envoyé, entre 0.0 et 1.0
+    pub loss: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct NetConfig {
+    pub local_player: Player,
+    /// Pas de retard appliqués aux entrées locales, pour laisser le temps au pair de les recevoir
+    pub input_delay: u32,
+    /// Au-delà de ce nombre de pas prédits sans confirmation, la simulation attend le pair
+    pub max_prediction: u32,
+    pub conditions: NetConditions,
+}
+
+impl Default for NetConfig {
+    fn default() -> Self {
+        Self {
+            local_player: Player::One,
+            input_delay: 2,
+            max_prediction: 8,
+            conditions: NetConditions::default(),
+        }
+    }
+}
+
+// Socket UDP non bloquante, avec latence et pertes simulées en émission
+struct Transport {
+    socket: UdpSocket,
+    peer: SocketAddr,
+    conditions: NetConditions,
+    rng: GameRng,
+    delayed: Vec<(Instant, Vec<u8>)>,
+}
+
+impl Transport {
+    fn send(&mut self, packet: Vec<u8>) {
+        let conditions = self.conditions;
+        if conditions.loss > 0.0 && self.rng.random_bool(conditions.loss.clamp(0.0, 1.0) as f64) {
+            return;
+        }
+
+        let jitter = if conditions.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            conditions.jitter.mul_f32(self.rng.random_range(0.0..=1.0))
+        };
+        let delay = conditions.latency + jitter;
+
+        if delay.is_zero() {
+            self.send_now(&packet);
+        } else {
+            self.delayed.push((Instant::now() + delay, packet));
+        }
+    }
+
+    fn send_now(&self, packet: &[u8]) {
+        // Un envoi raté équivaut à une perte : les entrées seront renvoyées
+        if let Err(e) = self.socket.send_to(packet, self.peer) {
+            log::debug!("send to {} failed: {}", self.peer, e);
+        }
+    }
+
+    fn flush(&mut self) {
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = self.delayed.drain(..).partition(|(at, _)| *at <= now);
+        self.delayed = waiting;
+        for (_, packet) in due {
+            self.send_now(&packet);
+        }
+    }
+
+    fn receive(&mut self) -> Vec<Vec<u8>> {
+        let mut packets = Vec::new();
+        let mut buffer = [0u8; 1500];
+
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((len, from)) if from == self.peer => packets.push(buffer[..len].to_vec()),
+                Ok(_) => {}
+                // Pair pas encore démarré ou déjà parti : on réessaiera au prochain appel
+                Err(e) if e.kind() == io::ErrorKind::ConnectionReset => {}
+                Err(_) => break,
+            }
+        }
+
+        packets
+    }
+}
+
+/// Partie à deux machines synchronisée par rollback, façon GGPO.
+///
+/// Chaque pas est simulé tout de suite avec l'entrée prédite du pair ; quand sa vraie entrée
+/// arrive et diffère, le moteur revient à l'état sauvegardé de ce pas et resimule jusqu'au présent.
+/// Les deux machines doivent créer leur `Engine` avec les mêmes règles et la même graine, sans contrôleur,
+/// puis ne le faire avancer que par la session.
+pub struct NetSession {
+    config: NetConfig,
+    transport: Transport,
+    frame: u64,
+    accumulator: f32,
+    /// Entrées locales par pas depuis le pas `pruned`, déjà décalées de `input_delay`
+    local_inputs: VecDeque<NetInput>,
+    /// Entrées confirmées du pair, contiguës depuis le pas `pruned`
+    remote_inputs: VecDeque<NetInput>,
+    /// Pas confirmés des deux côtés dont les entrées ont été oubliées
+    pruned: u64,
+    /// Entrées du pair arrivées dans le désordre
+    remote_pending: HashMap<u64, NetInput>,
+    /// Entrées du pair supposées pour les pas pas encore confirmés
+    predictions: HashMap<u64, NetInput>,
+    /// État du moteur avant chaque pas non confirmé
+    states: VecDeque<(u64, EngineState)>,
+    /// Nombre d'entrées locales reçues par le pair
+    peer_ack: u64,
+    /// Le pair nous a envoyé des entrées : il attend notre accusé de réception
+    ack_due: bool,
+    last_received: Option<Instant>,
+    rollbacks: u64,
+}
+
+impl NetSession {
+    pub fn new(config: NetConfig, bind: impl ToSocketAddrs, peer: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind(bind)?;
+        socket.set_nonblocking(true)?;
+        let peer = peer
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no peer address"))?;
+
+        Ok(Self {
+            transport: Transport {
+                socket,
+                peer,
+                conditions: config.conditions,
+                rng: GameRng::from_rng(&mut rand::rng()),
+                delayed: Vec::new(),
+            },
+            frame: 0,
+            accumulator: 0.0,
+            local_inputs: VecDeque::from(vec![NetInput::default(); config.input_delay as usize]),
+            remote_inputs: VecDeque::new(),
+            pruned: 0,
+            remote_pending: HashMap::new(),
+            predictions: HashMap::new(),
+            states: VecDeque::new(),
+            peer_ack: 0,
+            ack_due: false,
+            last_received: None,
+            rollbacks: 0,
+            config,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.transport.socket.local_addr()
+    }
+
+    /// Change l'adresse du pair ; les paquets venus d'une autre adresse sont ignorés
+    pub fn set_peer(&mut self, peer: SocketAddr) {
+        self.transport.peer = peer;
+    }
+
+    pub fn local_player(&self) -> Player {
+        self.config.local_player
+    }
+
+    /// Nombre de pas simulés, prédits compris
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Nombre de pas dont les deux entrées sont connues ; ceux-là ne seront plus resimulés
+    pub fn confirmed_frame(&self) -> u64 {
+        self.frame.min(self.remote_count())
+    }
+
+    /// Nombre d'entrées locales dont le pair a accusé réception
+    pub fn acknowledged_frame(&self) -> u64 {
+        self.peer_ack
+    }
+
+    pub fn rollbacks(&self) -> u64 {
+        self.rollbacks
+    }
+
+    /// Temps écoulé depuis le dernier paquet du pair ; `None` s'il ne s'est jamais manifesté
+    pub fn peer_silence(&self) -> Option<Duration> {
+        self.last_received.map(|at| at.elapsed())
+    }
+
+    /// Reçoit les entrées du pair et corrige la simulation si une prédiction était fausse
+    pub fn poll(&mut self, engine: &mut Engine) {
+        self.transport.flush();
+
+        let mut rollback_from: Option<u64> = None;
+        for packet in self.transport.receive() {
+            if let Some(frame) = self.receive_packet(&packet) {
+                rollback_from = Some(rollback_from.map_or(frame, |earliest| earliest.min(frame)));
+            }
+        }
+
+        if let Some(frame) = rollback_from {
+            self.rollback(engine, frame);
+        }
+
+        // Les états antérieurs au dernier pas confirmé ne serviront plus
+        let confirmed = self.confirmed_frame();
+        while self.states.front().is_some_and(|(frame, _)| *frame < confirmed) {
+            self.states.pop_front();
+        }
+
+        // Ni les entrées reçues par le pair et confirmées des deux côtés ; la dernière du pair sert encore à prédire
+        let prune_to = confirmed.min(self.peer_ack).saturating_sub(1);
+        while self.pruned < prune_to {
+            self.local_inputs.pop_front();
+            self.remote_inputs.pop_front();
+            self.pruned += 1;
+        }
+
+        if self.ack_due || self.peer_ack < self.local_count() {
+            self.send_inputs();
+        }
+    }
+
+    /// Simule un pas avec l'entrée locale ; `false` si le pair est trop en retard pour prédire plus loin
+    pub fn advance(&mut self, engine: &mut Engine, input: NetInput) -> bool {
+        self.poll(engine);
+
+        if self.frame - self.confirmed_frame() >= self.config.max_prediction as u64 {
+            return false;
+        }
+
+        self.local_inputs.push_back(input);
+        self.send_inputs();
+        self.simulate(engine, self.frame);
+        self.frame += 1;
+        true
+    }
+
+    /// Avance au rythme du moteur ; renvoie le nombre de pas simulés, les appuis ne comptent qu'une fois
+    pub fn update(&mut self, engine: &mut Engine, frame_time: Duration, input: NetInput) -> u32 {
+        let dt = engine.fixed_dt();
+        self.accumulator += frame_time.as_secs_f32();
+        self.poll(engine);
+
+        let mut input = input;
+        let mut steps = 0;
+        while self.accumulator >= dt && steps < engine.max_steps_per_frame {
+            if !self.advance(engine, input) {
+                // En attente du pair : inutile d'accumuler du retard à rattraper
+                self.accumulator = self.accumulator.min(dt);
+                break;
+            }
+            self.accumulator -= dt;
+            input = input.predicted();
+            steps += 1;
+        }
+        self.accumulator = self.accumulator.min(dt * engine.max_steps_per_frame as f32);
+
+        steps
+    }
+
+    fn simulate(&mut self, engine: &mut Engine, frame: u64) {
+        self.states.push_back((frame, engine.save_state()));
+
+        let local = self.local_inputs[(frame - self.pruned) as usize];
+        let remote = match self.remote_inputs.get((frame - self.pruned) as usize) {
+            Some(&input) => input,
+            None => {
+                let predicted = self.remote_inputs.back().copied().unwrap_or_default().predicted();
+                self.predictions.insert(frame, predicted);
+                predicted
+            }
+        };
+
+        let local_player = self.config.local_player;
+        let mut inputs = [local, remote];
+        if local_player == Player::Two {
+            inputs.reverse();
+        }
+
+        // Ordre fixe des joueurs : les deux machines appliquent les événements à l'identique
+        for input in inputs {
+            if input.serve {
+                engine.handle_event(GameEvent::Serve);
+            }
+            if input.pause {
+                engine.handle_event(GameEvent::TogglePause);
+            }
+        }
+        engine.physics.set_paddle_input(Player::One, inputs[0].paddle);
+        engine.physics.set_paddle_input(Player::Two, inputs[1].paddle);
+
+        let dt = engine.fixed_dt();
+        engine.step(dt);
+    }
+
+    fn rollback(&mut self, engine: &mut Engine, from: u64) {
+        let Some(index) = self.states.iter().position(|(frame, _)| *frame == from) else {
+            log::warn!("no saved state for frame {}, cannot roll back", from);
+            return;
+        };
+
+        engine.load_state(&self.states[index].1);
+        self.states.truncate(index);
+        self.rollbacks += 1;
+
+        // Les événements de ces pas ont déjà été émis lors de la prédiction
+        let events = std::mem::take(&mut engine.events);
+        for frame in from..self.frame {
+            self.simulate(engine, frame);
+        }
+        engine.events = events;
+    }
+
+    fn local_count(&self) -> u64 {
+        self.pruned + self.local_inputs.len() as u64
+    }
+
+    fn remote_count(&self) -> u64 {
+        self.pruned + self.remote_inputs.len() as u64
+    }
+
+    // Renvoie le premier pas dont la prédiction s'est révélée fausse
+    fn receive_packet(&mut self, packet: &[u8]) -> Option<u64> {
+        if packet.len() < 21 || &packet[..2] != MAGIC || packet[2] != PROTOCOL_VERSION {
+            return None;
+        }
+        self.last_received = Some(Instant::now());
+
+        let read_u64 = |at: usize| u64::from_le_bytes(packet[at..at + 8].try_into().unwrap());
+        self.peer_ack = self.peer_ack.max(read_u64(3));
+        let start = read_u64(11);
+        let count = u16::from_le_bytes([packet[19], packet[20]]) as usize;
+
+        let inputs = &packet[21..];
+        if inputs.len() < count * INPUT_SIZE {
+            return None;
+        }
+        // Un paquet sans entrée n'est qu'un accusé de réception, on n'y répond pas
+        self.ack_due |= count > 0;
+
+        for (offset, bytes) in inputs.chunks_exact(INPUT_SIZE).take(count).enumerate() {
+            let frame = start + offset as u64;
+            if frame >= self.remote_count() {
+                self.remote_pending.insert(frame, NetInput::decode(bytes));
+            }
+        }
+
+        let mut mispredicted = None;
+        while let Some(input) = self.remote_pending.remove(&self.remote_count()) {
+            let frame = self.remote_count();
+            if let Some(predicted) = self.predictions.remove(&frame)
+                && predicted != input
+                && mispredicted.is_none()
+            {
+                mispredicted = Some(frame);
+            }
+            self.remote_inputs.push_back(input);
+        }
+
+        mispredicted
+    }
+
+    // Paquet : en-tête, nombre d'entrées du pair reçues, puis nos entrées non acquittées
+    fn send_inputs(&mut self) {
+        let start = self.peer_ack.min(self.local_count());
+        let count = (self.local_count() - start).min(MAX_INPUTS_PER_PACKET as u64) as usize;
+        let inputs = self.local_inputs.range((start - self.pruned) as usize..).take(count);
+
+        let mut packet = Vec::with_capacity(21 + count * INPUT_SIZE);
+        packet.extend_from_slice(MAGIC);
+        packet.push(PROTOCOL_VERSION);
+        packet.extend_from_slice(&self.remote_count().to_le_bytes());
+        packet.extend_from_slice(&start.to_le_bytes());
+        packet.extend_from_slice(&(count as u16).to_le_bytes());
+        for input in inputs {
+            input.encode(&mut packet);
+        }
+
+        self.transport.send(packet);
+        self.ack_due = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_finite_paddle_decodes_as_idle() {
+        for paddle in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let mut bytes = Vec::new();
+            NetInput { paddle, serve: true, pause: false }.encode(&mut bytes);
+
+            let input = NetInput::decode(&bytes);
+            assert_eq!(input, NetInput { paddle: 0.0, serve: true, pause: false });
+        }
+    }
+
+    #[test]
+    fn input_round_trips() {
+        let input = NetInput { paddle: -0.25, serve: false, pause: true };
+        let mut bytes = Vec::new();
+        input.encode(&mut bytes);
+        assert_eq!(NetInput::decode(&bytes), input);
+    }
+}
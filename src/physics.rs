@@ -1,55 +1,578 @@
+use std::f32::consts::FRAC_PI_3;
+use glam::{vec2, Vec2};
+use crate::box_collider::{Collider, Contact, SweepHit};
 use crate::engine::Scene;
-use glam::vec2;
+use crate::events::{CollisionEvent, Side, WallSide};
+use crate::physics_world::{BodyHandle, BodyType};
+use crate::rigid_body::RigidBody;
+use crate::scoring::Player;
+
+/// Nombre maximal de rebonds résolus pendant un même pas
+const MAX_BOUNCES: usize = 8;
+
+/// Au-delà de cette abscisse, la balle a quitté l'arène
+pub const GOAL_LINE: f32 = 1.2;
+
+/// Les raquettes restent entre -ARENA_HALF_HEIGHT et ARENA_HALF_HEIGHT
+pub const ARENA_HALF_HEIGHT: f32 = 1.0;
+
+/// Angle de renvoi selon le point d'impact sur la raquette (« english »)
+#[derive(Clone, Copy, Debug)]
+pub struct Deflection {
+    /// Angle maximal par rapport à l'horizontale, atteint au bord de la raquette (radians)
+    pub max_angle: f32,
+    /// Part de la vitesse verticale de la raquette ajoutée au décalage d'impact
+    pub paddle_influence: f32,
+}
+
+impl Default for Deflection {
+    fn default() -> Self {
+        Self {
+            max_angle: FRAC_PI_3,
+            paddle_influence: 0.0,
+        }
+    }
+}
+
+impl Deflection {
+    /// Vitesse de sortie d'une balle touchant la face avant d'une raquette ; la norme est conservée.
+    /// `normal` est la normale du contact, dirigée de la raquette vers la balle.
+    pub fn deflect(
+        &self,
+        ball_position: Vec2,
+        ball_velocity: Vec2,
+        paddle_position: Vec2,
+        paddle: &Collider,
+        paddle_velocity: Vec2,
+        normal: Vec2,
+    ) -> Vec2 {
+        let offset = (ball_position.y - paddle_position.y) / paddle.half_size.y;
+        let offset = (offset + paddle_velocity.y * self.paddle_influence).clamp(-1.0, 1.0);
+        let angle = offset * self.max_angle;
+
+        // libm plutôt que le cos/sin de la plateforme : le rebond doit être identique partout
+        vec2(normal.x.signum() * libm::cosf(angle), libm::sinf(angle)) * ball_velocity.length()
+    }
+}
+
+/// Accélération de la balle au fil de l'échange
+#[derive(Clone, Copy, Debug)]
+pub struct RallySpeed {
+    /// Facteur appliqué à la vitesse de la balle à chaque renvoi
+    pub speed_up: f32,
+    pub max_speed: f32,
+    hits: u32,
+}
+
+impl RallySpeed {
+    pub fn new(speed_up: f32, max_speed: f32) -> Self {
+        Self {
+            speed_up,
+            max_speed,
+            hits: 0,
+        }
+    }
+
+    /// Nombre de renvois depuis le dernier service
+    pub fn hits(&self) -> u32 {
+        self.hits
+    }
+
+    pub fn reset(&mut self) {
+        self.hits = 0;
+    }
+
+    pub fn on_hit(&mut self, velocity: Vec2) -> Vec2 {
+        self.hits += 1;
+        let speed = (velocity.length() * self.speed_up).min(self.max_speed);
+        velocity.normalize_or_zero() * speed
+    }
+}
+
+impl Default for RallySpeed {
+    fn default() -> Self {
+        Self::new(1.05, 2.5)
+    }
+}
+
+/// Réglages de déplacement des raquettes
+#[derive(Clone, Copy, Debug)]
+pub struct PaddleMotion {
+    pub acceleration: f32,
+    pub max_speed: f32,
+}
+
+impl Default for PaddleMotion {
+    fn default() -> Self {
+        Self {
+            acceleration: 12.0,
+            max_speed: 1.6,
+        }
+    }
+}
 
 pub struct Physics {
     pub gravity: f32,
     pub scene: Scene,
+    pub deflection: Deflection,
+    pub rally: RallySpeed,
+    pub paddle_motion: PaddleMotion,
+    /// Direction demandée pour chaque raquette, entre -1.0 (bas) et 1.0 (haut)
+    pub paddle_input: [f32; 2],
+    /// Événements émis pendant le dernier appel à `update`
+    pub events: Vec<CollisionEvent>,
 }
 
 impl Physics {
+    /// Direction entre -1.0 et 1.0 ; une valeur non finie arrête la raquette
+    pub fn set_paddle_input(&mut self, player: Player, direction: f32) {
+        let direction = if direction.is_finite() { direction } else { 0.0 };
+        self.paddle_input[player.index()] = direction.clamp(-1.0, 1.0);
+    }
+
     pub fn update(&mut self, dt: f32) {
-        self.scene.ball.position += self.scene.ball.velocity * dt * 30.0;
+        self.events.clear();
+        self.update_kinematic(dt);
+
+        let handles = self.scene.world.handles();
 
-        let screen_top = 1.0;
-        let screen_bottom = -1.0;
+        for &handle in &handles {
+            if self.scene.world[handle].body_type == BodyType::Dynamic {
+                self.sweep_body(handle, &handles, dt);
+            }
+        }
+
+        // Un corps cinématique peut s'être déplacé sur un corps dynamique : on corrige le recouvrement restant
+        for &handle in &handles {
+            if self.scene.world[handle].body_type == BodyType::Dynamic {
+                self.resolve_overlaps(handle, &handles);
+            }
+        }
 
-        if self.scene.ball.position.y + self.scene.ball.collider.half_size.y >= screen_top {
-            self.scene.ball.position.y = screen_top - self.scene.ball.collider.half_size.y;
-            self.scene.ball.velocity.y = -self.scene.ball.velocity.y;
+        for &handle in &handles {
+            let body = &mut self.scene.world[handle];
+            if body.body_type == BodyType::Dynamic {
+                body.component.velocity.y -= self.gravity * dt;
+            }
         }
 
-        if self.scene.ball.position.y - self.scene.ball.collider.half_size.y <= screen_bottom {
-            self.scene.ball.position.y = screen_bottom + self.scene.ball.collider.half_size.y;
-            self.scene.ball.velocity.y = -self.scene.ball.velocity.y;
+        let ball_x = self.scene.component(self.scene.ball).position.x;
+        if ball_x < -GOAL_LINE {
+            self.events.push(CollisionEvent::BallLeftArena { side: Side::Left });
+        } else if ball_x > GOAL_LINE {
+            self.events.push(CollisionEvent::BallLeftArena { side: Side::Right });
         }
+    }
+
+    /// Déplace les raquettes et les autres corps cinématiques, sans collisions
+    pub fn update_kinematic(&mut self, dt: f32) {
+        for player in [Player::One, Player::Two] {
+            self.drive_paddle(player, dt);
+        }
+
+        for (_, body) in self.scene.world.iter_mut() {
+            if body.body_type == BodyType::Kinematic {
+                body.component.position += body.component.velocity * dt;
+            }
+        }
+
+        for player in [Player::One, Player::Two] {
+            self.clamp_paddle(player);
+        }
+    }
+
+    /// Déplace une seule raquette comme le ferait `update_kinematic` (prédiction côté client)
+    pub fn update_paddle(&mut self, player: Player, dt: f32) {
+        self.drive_paddle(player, dt);
+        let paddle = self.scene.component_mut(self.scene.paddle(player));
+        paddle.position += paddle.velocity * dt;
+        self.clamp_paddle(player);
+    }
+
+    // Rapproche la vitesse de la raquette de celle demandée, dans la limite de l'accélération
+    fn drive_paddle(&mut self, player: Player, dt: f32) {
+        let motion = self.paddle_motion;
+        let target = self.paddle_input[player.index()] * motion.max_speed;
+        let paddle = self.scene.component_mut(self.scene.paddle(player));
+
+        let max_change = motion.acceleration * dt;
+        paddle.velocity.x = 0.0;
+        paddle.velocity.y += (target - paddle.velocity.y).clamp(-max_change, max_change);
+    }
+
+    // ✅ Les raquettes ne sortent pas de l'arène
+    fn clamp_paddle(&mut self, player: Player) {
+        let paddle = self.scene.component_mut(self.scene.paddle(player));
+        let limit = ARENA_HALF_HEIGHT - paddle.collider.half_size.y;
 
-        if self.scene.ball.collider.collides_with(
-            self.scene.ball.position,
-            &self.scene.player1.collider,
-            self.scene.player1.position,
-        ) {
-            self.scene.ball.velocity.x = self.scene.ball.velocity.x.abs();
-            self.scene.ball.position.x = self.scene.player1.position.x
-                + self.scene.player1.collider.half_size.x
-                + self.scene.ball.collider.half_size.x;
+        if paddle.position.y.abs() > limit {
+            paddle.position.y = paddle.position.y.clamp(-limit, limit);
+            paddle.velocity.y = 0.0;
         }
+    }
 
-        if self.scene.ball.collider.collides_with(
-            self.scene.ball.position,
-            &self.scene.player2.collider,
-            self.scene.player2.position,
-        ) {
-            self.scene.ball.velocity.x = -self.scene.ball.velocity.x.abs();
-            self.scene.ball.position.x = self.scene.player2.position.x
-                - self.scene.player2.collider.half_size.x
-                - self.scene.ball.collider.half_size.x;
+    // Réponse propre au jeu après un choc, puis émission de l'événement correspondant
+    fn on_collision(&mut self, body: BodyHandle, other: BodyHandle, contact: Contact) {
+        let event = self.classify(body, other, contact);
+
+        // Seule la face avant renvoie la balle : un choc sur le dessus ou le dessous ne compte pas comme un renvoi
+        if matches!(event, CollisionEvent::BallHitPaddle { .. }) && contact.normal.x != 0.0 {
+            let paddle = &self.scene.world[other].component;
+            let ball = &self.scene.world[body].component;
+            let velocity = self.deflection.deflect(
+                ball.position,
+                ball.velocity,
+                paddle.position,
+                &paddle.collider,
+                paddle.velocity,
+                contact.normal,
+            );
+            let ball = self.scene.component_mut(body);
+            ball.velocity = self.rally.on_hit(velocity);
         }
 
-        if self.scene.ball.position.x < -1.2 || self.scene.ball.position.x > 1.2 {
-            self.scene.ball.position = vec2(0.0, 0.0);
-            self.scene.ball.velocity = vec2(0.02, 0.015);
+        self.events.push(event);
+    }
+
+    // Traduit un choc entre deux corps en événement de jeu
+    fn classify(&self, body: BodyHandle, other: BodyHandle, contact: Contact) -> CollisionEvent {
+        let scene = &self.scene;
+
+        if body != scene.ball {
+            CollisionEvent::BodiesCollided { body, other, contact }
+        } else if other == scene.player1 {
+            CollisionEvent::BallHitPaddle { paddle: Player::One, contact }
+        } else if other == scene.player2 {
+            CollisionEvent::BallHitPaddle { paddle: Player::Two, contact }
+        } else if other == scene.top_wall {
+            CollisionEvent::BallHitWall { side: WallSide::Top }
+        } else if other == scene.bottom_wall {
+            CollisionEvent::BallHitWall { side: WallSide::Bottom }
+        } else {
+            CollisionEvent::BodiesCollided { body, other, contact }
         }
+    }
+
+    // ✅ Déplace un corps dynamique en résolvant chaque impact au moment exact du contact
+    fn sweep_body(&mut self, handle: BodyHandle, handles: &[BodyHandle], dt: f32) {
+        let mut remaining = 1.0;
 
-        self.scene.ball.velocity.y -= self.gravity * dt;
+        for _ in 0..MAX_BOUNCES {
+            let body = &self.scene.world[handle].component;
+            let motion = body.velocity * dt * remaining;
+
+            let hit = handles
+                .iter()
+                // Un recouvrement déjà présent est corrigé par `resolve_overlaps`, pas à chaque rebond
+                .filter(|&&other| other != handle && self.contact(handle, other).is_none())
+                .filter_map(|&other| {
+                    let other_body = &self.scene.world[other].component;
+                    body.collider
+                        .sweep(body.position, motion, &other_body.collider, other_body.position)
+                        .map(|hit| (other, hit))
+                })
+                .min_by(|a, b| a.1.time.total_cmp(&b.1.time));
+
+            let Some((other_handle, SweepHit { time, normal })) = hit else {
+                self.scene.world[handle].component.position += motion;
+                break;
+            };
+
+            let Some((body, other)) = self.scene.world.pair_mut(handle, other_handle) else {
+                break;
+            };
+
+            body.component.position += motion * time;
+
+            // Point d'impact au centre de la face touchée
+            let contact = Contact {
+                normal,
+                penetration: 0.0,
+                point: body.component.position - normal * body.component.collider.half_size,
+            };
+
+            let (material, other_material) = (body.material(), other.material());
+            let impulse = collision_impulse(
+                &material,
+                body.component.velocity,
+                &other_material,
+                other.component.velocity,
+                normal,
+            );
+            material.apply_impulse(&mut body.component.velocity, impulse);
+            other_material.apply_impulse(&mut other.component.velocity, -impulse);
+
+            self.on_collision(handle, other_handle, contact);
+            remaining *= 1.0 - time;
+        }
     }
-}
\ No newline at end of file
+
+    fn resolve_overlaps(&mut self, handle: BodyHandle, handles: &[BodyHandle]) {
+        // Les murs d'abord : une raquette qui pousse la balle contre un mur passe en dernier
+        let mut ordered = handles.to_vec();
+        ordered.sort_by_key(|&other| self.scene.world[other].body_type == BodyType::Kinematic);
+
+        for other_handle in ordered {
+            let Some(mut contact) = self.contact(handle, other_handle) else {
+                continue;
+            };
+
+            if self.scene.world[other_handle].body_type == BodyType::Kinematic && contact.normal.y != 0.0 {
+                let body = &self.scene.world[handle].component;
+                let pushed = body.position + contact.normal * contact.penetration;
+                // Coincée contre un mur, la balle ne peut pas être repoussée verticalement : on la sort par le côté
+                if self.overlaps_static(handle, pushed) {
+                    contact = self.side_contact(handle, other_handle);
+                }
+            }
+
+            let Some((body, other)) = self.scene.world.pair_mut(handle, other_handle) else {
+                continue;
+            };
+
+            let (material, other_material) = (body.material(), other.material());
+            let ratio = separation_ratio(&material, &other_material);
+            separate(&mut body.component.position, &mut other.component.position, &contact, ratio);
+
+            let impulse = collision_impulse(
+                &material,
+                body.component.velocity,
+                &other_material,
+                other.component.velocity,
+                contact.normal,
+            );
+            material.apply_impulse(&mut body.component.velocity, impulse);
+            other_material.apply_impulse(&mut other.component.velocity, -impulse);
+
+            if impulse != Vec2::ZERO {
+                self.on_collision(handle, other_handle, contact);
+            }
+        }
+    }
+
+    fn contact(&self, handle: BodyHandle, other: BodyHandle) -> Option<Contact> {
+        if handle == other {
+            return None;
+        }
+        let (body, other) = (&self.scene.world[handle].component, &self.scene.world[other].component);
+        body.collider.contact(body.position, &other.collider, other.position)
+    }
+
+    // `handle` placé en `position` chevaucherait-il un corps statique ?
+    fn overlaps_static(&self, handle: BodyHandle, position: Vec2) -> bool {
+        let collider = &self.scene.world[handle].component.collider;
+        self.scene.world.iter().any(|(other, body)| {
+            other != handle
+                && body.body_type == BodyType::Static
+                && collider.contact(position, &body.component.collider, body.component.position).is_some()
+        })
+    }
+
+    // Contact le long de l'axe horizontal, quel que soit l'axe de recouvrement minimal
+    fn side_contact(&self, handle: BodyHandle, other: BodyHandle) -> Contact {
+        let (body, other) = (&self.scene.world[handle].component, &self.scene.world[other].component);
+        let delta = body.position - other.position;
+        let normal = vec2(delta.x.signum(), 0.0);
+        let penetration = body.collider.half_size.x + other.collider.half_size.x - delta.x.abs();
+
+        Contact {
+            normal,
+            penetration,
+            point: body.position - normal * body.collider.half_size,
+        }
+    }
+}
+
+/// Part de la séparation portée par `a`, répartie selon l'inverse des masses
+fn separation_ratio(a: &RigidBody, b: &RigidBody) -> f32 {
+    let total = a.inverse_mass() + b.inverse_mass();
+    if total == 0.0 {
+        0.5
+    } else {
+        a.inverse_mass() / total
+    }
+}
+
+/// Sépare deux corps le long de l'axe de translation minimale.
+/// `ratio` est la part de la correction portée par `a` (1.0 si `b` est immobile).
+pub fn separate(a: &mut Vec2, b: &mut Vec2, contact: &Contact, ratio: f32) {
+    let correction = contact.normal * contact.penetration;
+    *a += correction * ratio;
+    *b -= correction * (1.0 - ratio);
+}
+
+/// Impulsion à appliquer à `a` lors d'un choc avec `b`, `normal` étant dirigée de `b` vers `a`.
+/// La restitution retenue est la plus faible des deux corps.
+pub fn collision_impulse(a: &RigidBody, a_velocity: Vec2, b: &RigidBody, b_velocity: Vec2, normal: Vec2) -> Vec2 {
+    let inverse_mass = a.inverse_mass() + b.inverse_mass();
+    let approach = (a_velocity - b_velocity).dot(normal);
+
+    if inverse_mass == 0.0 || approach >= 0.0 {
+        return Vec2::ZERO;
+    }
+
+    let restitution = a.restitution.min(b.restitution);
+    normal * (-(1.0 + restitution) * approach / inverse_mass)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Engine;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn paddle_hits(physics: &Physics) -> usize {
+        physics
+            .events
+            .iter()
+            .filter(|event| matches!(event, CollisionEvent::BallHitPaddle { .. }))
+            .count()
+    }
+
+    // Balle arrivant de la droite sur la raquette de gauche, à `offset` demi-hauteurs de son centre
+    fn deflect_at(offset: f32) -> Vec2 {
+        let paddle = Collider::new(vec2(0.03, 0.2), [1.0; 3]);
+        Deflection::default().deflect(
+            vec2(-0.8, offset * 0.2),
+            vec2(-1.2, 0.5),
+            vec2(-0.85, 0.0),
+            &paddle,
+            Vec2::ZERO,
+            vec2(1.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn center_hit_returns_straight() {
+        let velocity = deflect_at(0.0);
+        assert!(velocity.x > 0.0);
+        assert!(velocity.y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn edge_hit_reaches_max_angle() {
+        let max_angle = Deflection::default().max_angle;
+        for (offset, sign) in [(1.0, 1.0), (-1.0, -1.0), (3.0, 1.0)] {
+            let velocity = deflect_at(offset);
+            assert!((velocity.y.atan2(velocity.x) - sign * max_angle).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn deflection_keeps_the_speed() {
+        let speed = vec2(-1.2, 0.5).length();
+        for offset in [-1.0, -0.4, 0.0, 0.7, 1.0] {
+            assert!((deflect_at(offset).length() - speed).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn only_front_face_hits_speed_up_the_rally() {
+        let mut engine = Engine::new();
+        let physics = &mut engine.physics;
+        let scene = &mut physics.scene;
+        // Balle tombant sur le dessus de la raquette de gauche
+        let ball = scene.component_mut(scene.ball);
+        ball.position = vec2(-0.85, 0.3);
+        ball.velocity = vec2(0.0, -1.0);
+
+        let mut hits = 0;
+        for _ in 0..10 {
+            physics.update(DT);
+            hits += paddle_hits(physics);
+        }
+        assert_eq!(hits, 1);
+        assert_eq!(physics.rally.hits(), 0);
+
+        // Puis sur sa face avant
+        let scene = &mut physics.scene;
+        let ball = scene.component_mut(scene.ball);
+        ball.position = vec2(-0.6, 0.0);
+        ball.velocity = vec2(-1.0, 0.0);
+        for _ in 0..20 {
+            physics.update(DT);
+        }
+        assert_eq!(physics.rally.hits(), 1);
+    }
+
+    #[test]
+    fn ball_pinned_against_a_wall_is_pushed_out_of_the_paddle() {
+        let mut engine = Engine::new();
+        let physics = &mut engine.physics;
+        let scene = &mut physics.scene;
+        let ball = scene.component_mut(scene.ball);
+        ball.position = vec2(-0.85, 0.97);
+        ball.velocity = vec2(0.01, 0.0);
+        physics.set_paddle_input(Player::One, 1.0);
+
+        let mut hits = 0;
+        for _ in 0..60 {
+            physics.update(DT);
+            hits += paddle_hits(physics);
+        }
+
+        let scene = &physics.scene;
+        let (ball, paddle) = (scene.component(scene.ball), scene.component(scene.player1));
+        assert!(hits <= 1, "{hits} paddle hits while pinned");
+        assert!(ball.collider.contact(ball.position, &paddle.collider, paddle.position).is_none());
+    }
+
+    // Balle de masse 1 arrivant à 2.0 sur une raquette immobile, normale dirigée vers la balle
+    fn bounce(restitution: f32) -> Vec2 {
+        let ball = RigidBody::new(1.0, restitution);
+        let paddle = RigidBody::kinematic(1.0);
+        let mut velocity = vec2(-2.0, 0.5);
+        let impulse = collision_impulse(&ball, velocity, &paddle, Vec2::ZERO, vec2(1.0, 0.0));
+        ball.apply_impulse(&mut velocity, impulse);
+        velocity
+    }
+
+    #[test]
+    fn elastic_impulse_keeps_the_normal_speed() {
+        let velocity = bounce(1.0);
+        assert!((velocity.x - 2.0).abs() < 1e-6);
+        // La composante tangentielle n'est pas touchée
+        assert_eq!(velocity.y, 0.5);
+    }
+
+    #[test]
+    fn inelastic_impulse_cancels_the_normal_speed() {
+        let velocity = bounce(0.0);
+        assert!(velocity.x.abs() < 1e-6);
+        assert_eq!(velocity.y, 0.5);
+    }
+
+    #[test]
+    fn separating_bodies_get_no_impulse() {
+        let ball = RigidBody::new(1.0, 1.0);
+        let paddle = RigidBody::kinematic(1.0);
+        let impulse = collision_impulse(&ball, vec2(2.0, 0.0), &paddle, Vec2::ZERO, vec2(1.0, 0.0));
+        assert_eq!(impulse, Vec2::ZERO);
+    }
+
+    #[test]
+    fn separate_never_moves_a_static_body() {
+        let contact = Contact {
+            normal: vec2(1.0, 0.0),
+            penetration: 0.01,
+            point: Vec2::ZERO,
+        };
+        let ball = RigidBody::new(1.0, 1.0);
+        let paddle = RigidBody::kinematic(1.0);
+
+        // Dans un sens comme dans l'autre, seule la balle se déplace
+        for (a, b, flip) in [(&ball, &paddle, 1.0), (&paddle, &ball, -1.0)] {
+            let contact = Contact {
+                normal: contact.normal * flip,
+                ..contact
+            };
+            let (mut a_pos, mut b_pos) = (Vec2::ZERO, Vec2::ZERO);
+            separate(&mut a_pos, &mut b_pos, &contact, separation_ratio(a, b));
+
+            let (ball_pos, paddle_pos) = if flip > 0.0 { (a_pos, b_pos) } else { (b_pos, a_pos) };
+            assert_eq!(paddle_pos, Vec2::ZERO);
+            assert!((ball_pos - vec2(0.01, 0.0)).length() < 1e-6);
+        }
+    }
+}
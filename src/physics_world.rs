@@ -0,0 +1,137 @@
+use std::ops::{Index, IndexMut};
+use crate::rigid_body::{Component, RigidBody};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BodyHandle(usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyType {
+    /// Ne bouge jamais (murs, obstacles)
+    Static,
+    /// Déplacé par le jeu, de masse infinie pour les collisions (raquettes)
+    Kinematic,
+    /// Intégré et soumis aux impulsions (balles)
+    Dynamic,
+}
+
+#[derive(Clone)]
+pub struct Body {
+    pub component: Component,
+    pub rigid_body: Option<RigidBody>,
+    pub body_type: BodyType,
+}
+
+impl Body {
+    pub fn new(component: Component, body_type: BodyType) -> Self {
+        Self {
+            component,
+            rigid_body: None,
+            body_type,
+        }
+    }
+
+    pub fn with_rigid_body(mut self, rigid_body: RigidBody) -> Self {
+        self.rigid_body = Some(rigid_body);
+        self
+    }
+
+    /// Propriétés utilisées pour les impulsions ; seuls les corps dynamiques ont une masse finie
+    pub fn material(&self) -> RigidBody {
+        let restitution = self.rigid_body.map_or(1.0, |body| body.restitution);
+        match self.body_type {
+            BodyType::Dynamic => self.rigid_body.unwrap_or(RigidBody::new(1.0, restitution)),
+            BodyType::Static | BodyType::Kinematic => RigidBody::kinematic(restitution),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PhysicsWorld {
+    // Les emplacements libérés ne sont pas réutilisés : un handle reste unique
+    bodies: Vec<Option<Body>>,
+}
+
+impl PhysicsWorld {
+    pub fn new() -> Self {
+        Self { bodies: Vec::new() }
+    }
+
+    pub fn insert(&mut self, body: Body) -> BodyHandle {
+        self.bodies.push(Some(body));
+        BodyHandle(self.bodies.len() - 1)
+    }
+
+    pub fn remove(&mut self, handle: BodyHandle) -> Option<Body> {
+        self.bodies.get_mut(handle.0).and_then(Option::take)
+    }
+
+    pub fn get(&self, handle: BodyHandle) -> Option<&Body> {
+        self.bodies.get(handle.0).and_then(Option::as_ref)
+    }
+
+    pub fn get_mut(&mut self, handle: BodyHandle) -> Option<&mut Body> {
+        self.bodies.get_mut(handle.0).and_then(Option::as_mut)
+    }
+
+    pub fn handles(&self) -> Vec<BodyHandle> {
+        self.iter().map(|(handle, _)| handle).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (BodyHandle, &Body)> {
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(index, body)| body.as_ref().map(|body| (BodyHandle(index), body)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (BodyHandle, &mut Body)> {
+        self.bodies
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, body)| body.as_mut().map(|body| (BodyHandle(index), body)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Accès mutable simultané à deux corps distincts
+    pub fn pair_mut(&mut self, a: BodyHandle, b: BodyHandle) -> Option<(&mut Body, &mut Body)> {
+        if a.0 == b.0 || a.0.max(b.0) >= self.bodies.len() {
+            return None;
+        }
+
+        let (low, high) = self.bodies.split_at_mut(a.0.max(b.0));
+        let (first, second) = (low[a.0.min(b.0)].as_mut()?, high[0].as_mut()?);
+
+        if a.0 < b.0 {
+            Some((first, second))
+        } else {
+            Some((second, first))
+        }
+    }
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Index<BodyHandle> for PhysicsWorld {
+    type Output = Body;
+
+    fn index(&self, handle: BodyHandle) -> &Body {
+        self.get(handle).expect("body handle no longer valid")
+    }
+}
+
+impl IndexMut<BodyHandle> for PhysicsWorld {
+    fn index_mut(&mut self, handle: BodyHandle) -> &mut Body {
+        self.get_mut(handle).expect("body handle no longer valid")
+    }
+}
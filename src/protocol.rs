@@ -0,0 +1,486 @@
+use glam::{vec2, Vec2};
+use crate::game_state::GameState;
+use crate::headless::Snapshot;
+use crate::net::{NetInput, INPUT_SIZE};
+use crate::scoring::{MatchResult, Player};
+use crate::spectator::DeltaFrame;
+
+const MAGIC: &[u8; 2] = b"RS";
+const PROTOCOL_VERSION: u8 = 1;
+
+/// Place demandée ou obtenue sur le serveur
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Player(Player),
+    Spectator,
+}
+
+/// Messages échangés entre le serveur de jeu et ses clients, un par datagramme UDP
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Message {
+    /// Client → serveur ; `None` prend la première raquette libre
+    Join { role: Option<Role> },
+    /// Serveur → client : place attribuée
+    Welcome { role: Role, tick_rate: f32 },
+    /// Serveur → client : plus de raquette libre
+    Rejected,
+    /// Client → serveur : entrée du pas numéro `sequence`
+    Input { sequence: u32, input: NetInput },
+    /// Spectateur → serveur : toujours là
+    Heartbeat,
+    Leave,
+    /// Serveur → client : état de la partie, avec la dernière entrée du destinataire prise en compte
+    Snapshot { ack: u32, snapshot: Snapshot },
+    /// Serveur → spectateur : état différé, en delta
+    SpectatorFrame(DeltaFrame),
+    /// Spectateur → serveur : image de référence perdue, renvoyer une image complète
+    Resync,
+}
+
+fn encode_role(role: Option<Role>) -> u8 {
+    match role {
+        None => 0,
+        Some(Role::Player(Player::One)) => 1,
+        Some(Role::Player(Player::Two)) => 2,
+        Some(Role::Spectator) => 3,
+    }
+}
+
+fn decode_role(byte: u8) -> Option<Option<Role>> {
+    match byte {
+        0 => Some(None),
+        1 => Some(Some(Role::Player(Player::One))),
+        2 => Some(Some(Role::Player(Player::Two))),
+        3 => Some(Some(Role::Spectator)),
+        _ => None,
+    }
+}
+
+fn encode_player(player: Player) -> u8 {
+    player.index() as u8
+}
+
+fn decode_player(byte: u8) -> Option<Player> {
+    match byte {
+        0 => Some(Player::One),
+        1 => Some(Player::Two),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.u32(value.to_bits());
+    }
+
+    fn vec2(&mut self, value: Vec2) {
+        self.f32(value.x);
+        self.f32(value.y);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.u32().map(f32::from_bits)
+    }
+
+    fn vec2(&mut self) -> Option<Vec2> {
+        Some(vec2(self.f32()?, self.f32()?))
+    }
+}
+
+fn write_state(writer: &mut Writer, state: GameState) {
+    match state {
+        GameState::Title => writer.u8(0),
+        GameState::ServeCountdown { remaining } => {
+            writer.u8(1);
+            writer.f32(remaining);
+        }
+        GameState::Rally => writer.u8(2),
+        GameState::PointScored { scorer, remaining } => {
+            writer.u8(3);
+            writer.u8(encode_player(scorer));
+            writer.f32(remaining);
+        }
+        GameState::Paused => writer.u8(4),
+        GameState::GameOver { result } => {
+            writer.u8(5);
+            writer.u8(encode_player(result.winner));
+            writer.u32(result.sets[0]);
+            writer.u32(result.sets[1]);
+        }
+    }
+}
+
+fn read_state(reader: &mut Reader) -> Option<GameState> {
+    Some(match reader.u8()? {
+        0 => GameState::Title,
+        1 => GameState::ServeCountdown { remaining: reader.f32()? },
+        2 => GameState::Rally,
+        3 => GameState::PointScored {
+            scorer: decode_player(reader.u8()?)?,
+            remaining: reader.f32()?,
+        },
+        4 => GameState::Paused,
+        5 => GameState::GameOver {
+            result: MatchResult {
+                winner: decode_player(reader.u8()?)?,
+                sets: [reader.u32()?, reader.u32()?],
+            },
+        },
+        _ => return None,
+    })
+}
+
+fn write_snapshot(writer: &mut Writer, snapshot: &Snapshot) {
+    writer.u64(snapshot.tick);
+    write_state(writer, snapshot.state);
+    writer.vec2(snapshot.ball_position);
+    writer.vec2(snapshot.ball_velocity);
+    for index in 0..2 {
+        writer.vec2(snapshot.paddle_positions[index]);
+        writer.vec2(snapshot.paddle_velocities[index]);
+        writer.u32(snapshot.points[index]);
+        writer.u32(snapshot.sets[index]);
+    }
+    writer.u8(encode_player(snapshot.server));
+    writer.u32(snapshot.rally_hits);
+}
+
+fn read_snapshot(reader: &mut Reader) -> Option<Snapshot> {
+    let tick = reader.u64()?;
+    let state = read_state(reader)?;
+    let ball_position = reader.vec2()?;
+    let ball_velocity = reader.vec2()?;
+
+    let mut paddle_positions = [Vec2::ZERO; 2];
+    let mut paddle_velocities = [Vec2::ZERO; 2];
+    let mut points = [0; 2];
+    let mut sets = [0; 2];
+    for index in 0..2 {
+        paddle_positions[index] = reader.vec2()?;
+        paddle_velocities[index] = reader.vec2()?;
+        points[index] = reader.u32()?;
+        sets[index] = reader.u32()?;
+    }
+
+    Some(Snapshot {
+        tick,
+        state,
+        ball_position,
+        ball_velocity,
+        paddle_positions,
+        paddle_velocities,
+        points,
+        sets,
+        server: decode_player(reader.u8()?)?,
+        rally_hits: reader.u32()?,
+    })
+}
+
+// Un bit par champ présent dans l'image, dans l'ordre d'écriture
+const FRAME_BASE: u16 = 1 << 0;
+const FRAME_STATE: u16 = 1 << 1;
+const FRAME_BALL_POSITION: u16 = 1 << 2;
+const FRAME_BALL_VELOCITY: u16 = 1 << 3;
+const FRAME_PADDLE_POSITIONS: [u16; 2] = [1 << 4, 1 << 5];
+const FRAME_PADDLE_VELOCITIES: [u16; 2] = [1 << 6, 1 << 7];
+const FRAME_POINTS: u16 = 1 << 8;
+const FRAME_SETS: u16 = 1 << 9;
+const FRAME_SERVER: u16 = 1 << 10;
+const FRAME_RALLY_HITS: u16 = 1 << 11;
+
+fn write_frame(writer: &mut Writer, frame: &DeltaFrame) {
+    let flag = |present: bool, bit: u16| if present { bit } else { 0 };
+    let mask = flag(frame.base.is_some(), FRAME_BASE)
+        | flag(frame.state.is_some(), FRAME_STATE)
+        | flag(frame.ball_position.is_some(), FRAME_BALL_POSITION)
+        | flag(frame.ball_velocity.is_some(), FRAME_BALL_VELOCITY)
+        | flag(frame.paddle_positions[0].is_some(), FRAME_PADDLE_POSITIONS[0])
+        | flag(frame.paddle_positions[1].is_some(), FRAME_PADDLE_POSITIONS[1])
+        | flag(frame.paddle_velocities[0].is_some(), FRAME_PADDLE_VELOCITIES[0])
+        | flag(frame.paddle_velocities[1].is_some(), FRAME_PADDLE_VELOCITIES[1])
+        | flag(frame.points.is_some(), FRAME_POINTS)
+        | flag(frame.sets.is_some(), FRAME_SETS)
+        | flag(frame.server.is_some(), FRAME_SERVER)
+        | flag(frame.rally_hits.is_some(), FRAME_RALLY_HITS);
+
+    writer.bytes.extend_from_slice(&mask.to_le_bytes());
+    writer.u64(frame.tick);
+    if let Some(base) = frame.base {
+        writer.u64(base);
+    }
+    if let Some(state) = frame.state {
+        write_state(writer, state);
+    }
+    let vectors = [frame.ball_position, frame.ball_velocity]
+        .into_iter()
+        .chain(frame.paddle_positions)
+        .chain(frame.paddle_velocities);
+    for value in vectors.flatten() {
+        writer.vec2(value);
+    }
+    for [one, two] in [frame.points, frame.sets].into_iter().flatten() {
+        writer.u32(one);
+        writer.u32(two);
+    }
+    if let Some(server) = frame.server {
+        writer.u8(encode_player(server));
+    }
+    if let Some(rally_hits) = frame.rally_hits {
+        writer.u32(rally_hits);
+    }
+}
+
+fn read_frame(reader: &mut Reader) -> Option<DeltaFrame> {
+    let mask = reader.take(2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))?;
+    let has = |bit: u16| mask & bit != 0;
+
+    let mut frame = DeltaFrame {
+        tick: reader.u64()?,
+        ..DeltaFrame::default()
+    };
+    if has(FRAME_BASE) {
+        frame.base = Some(reader.u64()?);
+    }
+    if has(FRAME_STATE) {
+        frame.state = Some(read_state(reader)?);
+    }
+    if has(FRAME_BALL_POSITION) {
+        frame.ball_position = Some(reader.vec2()?);
+    }
+    if has(FRAME_BALL_VELOCITY) {
+        frame.ball_velocity = Some(reader.vec2()?);
+    }
+    for (index, bit) in FRAME_PADDLE_POSITIONS.into_iter().enumerate() {
+        if has(bit) {
+            frame.paddle_positions[index] = Some(reader.vec2()?);
+        }
+    }
+    for (index, bit) in FRAME_PADDLE_VELOCITIES.into_iter().enumerate() {
+        if has(bit) {
+            frame.paddle_velocities[index] = Some(reader.vec2()?);
+        }
+    }
+    if has(FRAME_POINTS) {
+        frame.points = Some([reader.u32()?, reader.u32()?]);
+    }
+    if has(FRAME_SETS) {
+        frame.sets = Some([reader.u32()?, reader.u32()?]);
+    }
+    if has(FRAME_SERVER) {
+        frame.server = Some(decode_player(reader.u8()?)?);
+    }
+    if has(FRAME_RALLY_HITS) {
+        frame.rally_hits = Some(reader.u32()?);
+    }
+
+    Some(frame)
+}
+
+/// Image de flux spectateur seule, pour l'enregistrer sur disque
+pub(crate) fn encode_frame(frame: &DeltaFrame) -> Vec<u8> {
+    let mut writer = Writer::default();
+    write_frame(&mut writer, frame);
+    writer.bytes
+}
+
+pub(crate) fn decode_frame(bytes: &[u8]) -> Option<DeltaFrame> {
+    read_frame(&mut Reader { bytes })
+}
+
+impl Message {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.bytes.extend_from_slice(MAGIC);
+        writer.u8(PROTOCOL_VERSION);
+
+        match *self {
+            Message::Join { role } => {
+                writer.u8(1);
+                writer.u8(encode_role(role));
+            }
+            Message::Welcome { role, tick_rate } => {
+                writer.u8(2);
+                writer.u8(encode_role(Some(role)));
+                writer.f32(tick_rate);
+            }
+            Message::Rejected => writer.u8(3),
+            Message::Input { sequence, input } => {
+                writer.u8(4);
+                writer.u32(sequence);
+                input.encode(&mut writer.bytes);
+            }
+            Message::Heartbeat => writer.u8(5),
+            Message::Leave => writer.u8(6),
+            Message::Snapshot { ack, ref snapshot } => {
+                writer.u8(7);
+                writer.u32(ack);
+                write_snapshot(&mut writer, snapshot);
+            }
+            Message::SpectatorFrame(ref frame) => {
+                writer.u8(8);
+                write_frame(&mut writer, frame);
+            }
+            Message::Resync => writer.u8(9),
+        }
+
+        writer.bytes
+    }
+
+    /// `None` pour un datagramme d'une autre application, d'une autre version ou tronqué
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader { bytes };
+        if reader.take(2)? != MAGIC || reader.u8()? != PROTOCOL_VERSION {
+            return None;
+        }
+
+        let message = match reader.u8()? {
+            1 => Message::Join {
+                role: decode_role(reader.u8()?)?,
+            },
+            2 => Message::Welcome {
+                role: decode_role(reader.u8()?)??,
+                tick_rate: reader.f32()?,
+            },
+            3 => Message::Rejected,
+            4 => Message::Input {
+                sequence: reader.u32()?,
+                input: NetInput::decode(reader.take(INPUT_SIZE)?),
+            },
+            5 => Message::Heartbeat,
+            6 => Message::Leave,
+            7 => Message::Snapshot {
+                ack: reader.u32()?,
+                snapshot: read_snapshot(&mut reader)?,
+            },
+            8 => Message::SpectatorFrame(read_frame(&mut reader)?),
+            9 => Message::Resync,
+            _ => return None,
+        };
+
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Engine;
+
+    fn snapshot() -> Snapshot {
+        let mut snapshot = Snapshot::of(&Engine::new());
+        snapshot.tick = 42;
+        snapshot.state = GameState::PointScored {
+            scorer: Player::Two,
+            remaining: 0.5,
+        };
+        snapshot.ball_velocity = vec2(-0.7, 0.3);
+        snapshot.points = [3, 5];
+        snapshot.server = Player::Two;
+        snapshot
+    }
+
+    #[test]
+    fn every_message_round_trips() {
+        let messages = [
+            Message::Join { role: None },
+            Message::Join {
+                role: Some(Role::Player(Player::Two)),
+            },
+            Message::Welcome {
+                role: Role::Spectator,
+                tick_rate: 60.0,
+            },
+            Message::Rejected,
+            Message::Input {
+                sequence: 7,
+                input: NetInput {
+                    paddle: -0.5,
+                    serve: true,
+                    pause: true,
+                },
+            },
+            Message::Heartbeat,
+            Message::Leave,
+            Message::Snapshot {
+                ack: 6,
+                snapshot: snapshot(),
+            },
+            Message::SpectatorFrame(DeltaFrame::between(None, &snapshot())),
+            Message::SpectatorFrame(DeltaFrame::between(
+                Some(&snapshot()),
+                &Snapshot {
+                    tick: 45,
+                    state: GameState::GameOver {
+                        result: MatchResult {
+                            winner: Player::One,
+                            sets: [2, 1],
+                        },
+                    },
+                    ..snapshot()
+                },
+            )),
+            Message::Resync,
+        ];
+
+        for message in messages {
+            assert_eq!(Message::decode(&message.encode()), Some(message));
+        }
+    }
+
+    #[test]
+    fn truncated_or_foreign_datagrams_are_rejected() {
+        let bytes = Message::Snapshot {
+            ack: 1,
+            snapshot: snapshot(),
+        }
+        .encode();
+
+        assert_eq!(Message::decode(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(Message::decode(b"XX\x01\x05"), None);
+        assert_eq!(Message::decode(&[]), None);
+    }
+}
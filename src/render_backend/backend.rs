@@ -0,0 +1,212 @@
+use std::fmt;
+use std::path::Path;
+
+/// Variable d'environnement choisissant l'API graphique (`vulkan`, `gl`, `metal`, `dx12`, `auto`)
+pub const BACKEND_ENV: &str = "RS_PONG_BACKEND";
+/// Variable d'environnement forçant un adaptateur logiciel (`1` ou `true`)
+pub const FALLBACK_ADAPTER_ENV: &str = "RS_PONG_FALLBACK_ADAPTER";
+
+/// API graphique demandée à wgpu
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BackendChoice {
+    /// La meilleure API native de la plateforme (Vulkan, Metal ou DX12)
+    #[default]
+    Auto,
+    Vulkan,
+    Gl,
+    Metal,
+    Dx12,
+}
+
+impl BackendChoice {
+    pub const ALL: [BackendChoice; 5] = [
+        BackendChoice::Auto,
+        BackendChoice::Vulkan,
+        BackendChoice::Gl,
+        BackendChoice::Metal,
+        BackendChoice::Dx12,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BackendChoice::Auto => "auto",
+            BackendChoice::Vulkan => "vulkan",
+            BackendChoice::Gl => "gl",
+            BackendChoice::Metal => "metal",
+            BackendChoice::Dx12 => "dx12",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|choice| choice.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn backends(self) -> wgpu::Backends {
+        match self {
+            BackendChoice::Auto => wgpu::Backends::PRIMARY,
+            BackendChoice::Vulkan => wgpu::Backends::VULKAN,
+            BackendChoice::Gl => wgpu::Backends::GL,
+            BackendChoice::Metal => wgpu::Backends::METAL,
+            BackendChoice::Dx12 => wgpu::Backends::DX12,
+        }
+    }
+
+    /// Backends essayés dans l'ordre : celui demandé, puis les API natives, puis GL en dernier recours
+    pub fn candidates(self) -> Vec<wgpu::Backends> {
+        let mut candidates = vec![self.backends()];
+        for fallback in [wgpu::Backends::PRIMARY, wgpu::Backends::GL] {
+            if !candidates.contains(&fallback) {
+                candidates.push(fallback);
+            }
+        }
+        candidates
+    }
+}
+
+/// Réglages du rendu : fichier de configuration, puis variables d'environnement, puis ligne de commande
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GraphicsConfig {
+    pub backend: BackendChoice,
+    /// N'accepte qu'un adaptateur logiciel (lavapipe, llvmpipe, WARP...)
+    pub force_fallback_adapter: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GraphicsConfigError {
+    UnknownBackend { line: usize, name: String },
+    UnknownSetting { line: usize, name: String },
+    InvalidValue { line: usize, value: String },
+    Syntax { line: usize },
+}
+
+impl fmt::Display for GraphicsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphicsConfigError::UnknownBackend { line, name } => write!(
+                f,
+                "line {line}: unknown backend `{name}` (expected one of {})",
+                BackendChoice::ALL.map(BackendChoice::name).join(", ")
+            ),
+            GraphicsConfigError::UnknownSetting { line, name } => write!(f, "line {line}: unknown setting `{name}`"),
+            GraphicsConfigError::InvalidValue { line, value } => write!(f, "line {line}: invalid value `{value}`"),
+            GraphicsConfigError::Syntax { line } => write!(f, "line {line}: expected `setting = value`"),
+        }
+    }
+}
+
+impl std::error::Error for GraphicsConfigError {}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+impl GraphicsConfig {
+    /// Lit une configuration `backend = vulkan` / `fallback_adapter = true`, `#` pour les commentaires
+    pub fn from_config(text: &str) -> Result<Self, GraphicsConfigError> {
+        let mut config = Self::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (setting, value) = line
+                .split_once('=')
+                .ok_or(GraphicsConfigError::Syntax { line: line_number })?;
+            let (setting, value) = (setting.trim(), value.trim());
+
+            match setting {
+                "backend" => {
+                    config.backend = BackendChoice::from_name(value).ok_or_else(|| GraphicsConfigError::UnknownBackend {
+                        line: line_number,
+                        name: value.to_string(),
+                    })?;
+                }
+                "fallback_adapter" => {
+                    config.force_fallback_adapter = parse_flag(value).ok_or_else(|| GraphicsConfigError::InvalidValue {
+                        line: line_number,
+                        value: value.to_string(),
+                    })?;
+                }
+                _ => {
+                    return Err(GraphicsConfigError::UnknownSetting {
+                        line: line_number,
+                        name: setting.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Self::from_config(&text)?)
+    }
+
+    /// Applique `RS_PONG_BACKEND` et `RS_PONG_FALLBACK_ADAPTER` ; une valeur invalide est ignorée
+    pub fn with_env(mut self) -> Self {
+        if let Ok(name) = std::env::var(BACKEND_ENV) {
+            match BackendChoice::from_name(&name) {
+                Some(backend) => self.backend = backend,
+                None => log::warn!("Ignoring {}={}: unknown backend", BACKEND_ENV, name),
+            }
+        }
+        if let Ok(value) = std::env::var(FALLBACK_ADAPTER_ENV) {
+            match parse_flag(&value) {
+                Some(force) => self.force_fallback_adapter = force,
+                None => log::warn!("Ignoring {}={}: expected true or false", FALLBACK_ADAPTER_ENV, value),
+            }
+        }
+        self
+    }
+}
+
+/// Un backend essayé sans succès, avec les adaptateurs qu'il proposait
+#[derive(Debug)]
+pub struct AdapterAttempt {
+    pub backends: wgpu::Backends,
+    pub adapters: Vec<String>,
+    pub error: String,
+}
+
+/// Aucun backend n'a fourni d'adaptateur utilisable
+#[derive(Debug)]
+pub struct NoAdapterError {
+    pub config: GraphicsConfig,
+    pub attempts: Vec<AdapterAttempt>,
+}
+
+impl fmt::Display for NoAdapterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no usable graphics adapter (requested backend `{}`", self.config.backend.name())?;
+        if self.config.force_fallback_adapter {
+            write!(f, ", software adapter only")?;
+        }
+        write!(f, ")")?;
+
+        for attempt in &self.attempts {
+            write!(f, "\n  {:?}: {}", attempt.backends, attempt.error)?;
+            if attempt.adapters.is_empty() {
+                write!(f, "\n    no adapter found")?;
+            }
+            for adapter in &attempt.adapters {
+                write!(f, "\n    tried {adapter}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for NoAdapterError {}
+
+pub fn describe_adapter(info: &wgpu::AdapterInfo) -> String {
+    format!("{} ({:?}, {:?})", info.name, info.backend, info.device_type)
+}
@@ -1,10 +1,12 @@
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "render", derive(bytemuck::Pod, bytemuck::Zeroable))]
 pub struct Vertex {
-    pub(crate) position: [f32; 3],
-    pub(crate) color: [f32; 3],
+    pub position: [f32; 3],
+    pub color: [f32; 3],
 }
 
+#[cfg(feature = "render")]
 impl Vertex {
     pub fn desc() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
@@ -1,49 +1,159 @@
 use std::sync::Arc;
 use wgpu;
 use winit::window::Window;
+use crate::render_backend::backend::{describe_adapter, AdapterAttempt, GraphicsConfig, NoAdapterError};
+use crate::render_backend::image::Image;
 
-/// Encapsule le contexte WGPU (device, queue, surface)
-pub struct WgpuContext {
-    pub device: wgpu::Device,
-    pub queue: wgpu::Queue,
+/// Format des images hors écran, lisible tel quel dans un PNG
+pub const OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Surface d'une fenêtre
+pub struct SurfaceTarget {
     pub surface: wgpu::Surface<'static>,
     pub config: wgpu::SurfaceConfiguration,
     is_configured: bool,
 }
 
+/// Texture hors écran, relue après le rendu
+pub struct OffscreenTarget {
+    pub texture: wgpu::Texture,
+}
+
+impl OffscreenTarget {
+    fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Target"),
+            size: wgpu::Extent3d {
+                width: width.max(1),
+                height: height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: OFFSCREEN_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        Self { texture }
+    }
+}
+
+/// Où `State::render` dessine
+pub enum RenderTarget {
+    Surface(SurfaceTarget),
+    Offscreen(OffscreenTarget),
+}
+
+/// Encapsule le contexte WGPU (device, queue, cible de rendu)
+pub struct WgpuContext {
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub target: RenderTarget,
+}
+
 impl WgpuContext {
-    pub async fn new(window: Arc<Window>) -> anyhow::Result<Self> {
+    /// Essaie les backends de `graphics` dans l'ordre jusqu'à obtenir un adaptateur compatible avec la fenêtre
+    pub async fn new(window: Arc<Window>, graphics: &GraphicsConfig) -> anyhow::Result<Self> {
         let size = window.inner_size();
+        let (surface, adapter, device, queue) = Self::request(Some(window), graphics).await?;
+        let surface = surface.expect("a surface is created for every window");
+        let target = RenderTarget::Surface(Self::surface_target(surface, &adapter, size.width, size.height));
+
+        Ok(Self { device, queue, target })
+    }
+
+    /// Contexte sans fenêtre, qui dessine dans une texture de `width` × `height`
+    pub async fn offscreen(width: u32, height: u32, graphics: &GraphicsConfig) -> anyhow::Result<Self> {
+        let (_, _, device, queue) = Self::request(None, graphics).await?;
+        let target = RenderTarget::Offscreen(OffscreenTarget::new(&device, width, height));
+
+        Ok(Self { device, queue, target })
+    }
+
+    async fn request(
+        window: Option<Arc<Window>>,
+        graphics: &GraphicsConfig,
+    ) -> Result<(Option<wgpu::Surface<'static>>, wgpu::Adapter, wgpu::Device, wgpu::Queue), NoAdapterError> {
+        let mut attempts = Vec::new();
 
+        for backends in graphics.backend.candidates() {
+            match Self::open(window.clone(), backends, graphics).await {
+                Ok((surface, adapter, device, queue)) => {
+                    log::info!("Using adapter {}", describe_adapter(&adapter.get_info()));
+                    return Ok((surface, adapter, device, queue));
+                }
+                Err(attempt) => {
+                    log::warn!("Backend {:?} unavailable: {}", backends, attempt.error);
+                    attempts.push(attempt);
+                }
+            }
+        }
+
+        Err(NoAdapterError {
+            config: *graphics,
+            attempts,
+        })
+    }
+
+    async fn open(
+        window: Option<Arc<Window>>,
+        backends: wgpu::Backends,
+        graphics: &GraphicsConfig,
+    ) -> Result<(Option<wgpu::Surface<'static>>, wgpu::Adapter, wgpu::Device, wgpu::Queue), AdapterAttempt> {
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::DX12,
+            backends,
             ..Default::default()
         });
+        let adapters = instance
+            .enumerate_adapters(backends)
+            .iter()
+            .map(|adapter| describe_adapter(&adapter.get_info()))
+            .collect();
+        let failed = |error: String| AdapterAttempt {
+            backends,
+            adapters,
+            error,
+        };
 
-        let surface = instance.create_surface(window.clone())?;
+        let surface = match window.map(|window| instance.create_surface(window)).transpose() {
+            Ok(surface) => surface,
+            Err(e) => return Err(failed(format!("cannot create surface: {e}"))),
+        };
 
-        let adapter = instance
+        let adapter = match instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: Default::default(),
-                force_fallback_adapter: false,
-                compatible_surface: None,
+                force_fallback_adapter: graphics.force_fallback_adapter,
+                compatible_surface: surface.as_ref(),
             })
-            .await?;
+            .await
+        {
+            Ok(adapter) => adapter,
+            Err(e) => return Err(failed(e.to_string())),
+        };
 
-        let (device, queue) = adapter
+        match adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: Some("Device"),
                     required_features: wgpu::Features::empty(),
                     experimental_features: wgpu::ExperimentalFeatures::disabled(),
-                    required_limits: wgpu::Limits::default(),
+                    required_limits: wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits()),
                     trace: wgpu::Trace::Off,
                     ..Default::default()
                 },
             )
-            .await?;
+            .await
+        {
+            Ok((device, queue)) => Ok((surface, adapter, device, queue)),
+            Err(e) => Err(failed(format!("{}: {e}", describe_adapter(&adapter.get_info())))),
+        }
+    }
 
-        let surface_caps = surface.get_capabilities(&adapter);
+    fn surface_target(surface: wgpu::Surface<'static>, adapter: &wgpu::Adapter, width: u32, height: u32) -> SurfaceTarget {
+        let surface_caps = surface.get_capabilities(adapter);
         let surface_format = surface_caps
             .formats
             .iter()
@@ -54,37 +164,123 @@ impl WgpuContext {
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
-            width: size.width.max(1),
-            height: size.height.max(1),
+            width: width.max(1),
+            height: height.max(1),
             present_mode: surface_caps.present_modes[0],
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
 
-        Ok(Self {
-            device,
-            queue,
+        SurfaceTarget {
             surface,
             config,
             is_configured: false,
-        })
+        }
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
-        if width > 0 && height > 0 {
-            self.config.width = width;
-            self.config.height = height;
-            self.surface.configure(&self.device, &self.config);
-            self.is_configured = true;
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        match &mut self.target {
+            RenderTarget::Surface(target) => {
+                target.config.width = width;
+                target.config.height = height;
+                target.surface.configure(&self.device, &target.config);
+                target.is_configured = true;
+            }
+            RenderTarget::Offscreen(target) => *target = OffscreenTarget::new(&self.device, width, height),
         }
     }
 
     pub fn is_configured(&self) -> bool {
-        self.is_configured
+        match &self.target {
+            RenderTarget::Surface(target) => target.is_configured,
+            RenderTarget::Offscreen(_) => true,
+        }
     }
 
     pub fn format(&self) -> wgpu::TextureFormat {
-        self.config.format
+        match &self.target {
+            RenderTarget::Surface(target) => target.config.format,
+            RenderTarget::Offscreen(_) => OFFSCREEN_FORMAT,
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        match &self.target {
+            RenderTarget::Surface(target) => (target.config.width, target.config.height),
+            RenderTarget::Offscreen(target) => (target.texture.width(), target.texture.height()),
+        }
+    }
+
+    /// Texture où dessiner l'image suivante ; celle d'une fenêtre doit ensuite être présentée
+    pub fn acquire(&self) -> Result<(Option<wgpu::SurfaceTexture>, wgpu::TextureView), wgpu::SurfaceError> {
+        let view = wgpu::TextureViewDescriptor::default();
+        match &self.target {
+            RenderTarget::Surface(target) => {
+                let output = target.surface.get_current_texture()?;
+                let view = output.texture.create_view(&view);
+                Ok((Some(output), view))
+            }
+            RenderTarget::Offscreen(target) => Ok((None, target.texture.create_view(&view))),
+        }
     }
-}
\ No newline at end of file
+
+    /// Relit la texture hors écran ; impossible avec la surface d'une fenêtre
+    pub fn read_pixels(&self) -> anyhow::Result<Image> {
+        let RenderTarget::Offscreen(target) = &self.target else {
+            anyhow::bail!("cannot read back a window surface, use an offscreen context");
+        };
+
+        let (width, height) = (target.texture.width(), target.texture.height());
+        // Chaque ligne copiée doit être alignée sur 256 octets
+        let row_bytes = width * 4;
+        let padded_row_bytes = row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_row_bytes * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Readback Encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            target.texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: Some(height),
+                },
+            },
+            target.texture.size(),
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::PollType::wait_indefinitely())?;
+        receiver.recv()??;
+
+        let mapped = buffer.slice(..).get_mapped_range();
+        let mut pixels = Vec::with_capacity((row_bytes * height) as usize);
+        for row in mapped.chunks(padded_row_bytes as usize) {
+            pixels.extend_from_slice(&row[..row_bytes as usize]);
+        }
+        drop(mapped);
+        buffer.unmap();
+
+        Ok(Image::from_pixels(width, height, pixels))
+    }
+}
@@ -0,0 +1,121 @@
+use glam::{Vec2, Vec3};
+use crate::render_backend::image::Image;
+use crate::render_backend::renderer::{RenderError, Renderer, CLEAR_COLOR};
+use crate::render_backend::scene::Scene;
+
+const SUBPIXELS: f32 = 256.0;
+
+/// Rendu logiciel, sans GPU : les triangles de la scène sont remplis dans une image RGBA.
+///
+/// Suit les mêmes règles que le pipeline wgpu (centre des pixels, règle haut-gauche,
+/// couleurs interpolées puis encodées en sRGB) pour donner les mêmes images.
+pub struct CpuRenderer {
+    framebuffer: Image,
+}
+
+impl CpuRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            framebuffer: Image::new(width.max(1), height.max(1), encode(CLEAR_COLOR)),
+        }
+    }
+
+    pub fn framebuffer(&self) -> &Image {
+        &self.framebuffer
+    }
+
+    // Triangle en coordonnées écran (pixels, y vers le bas)
+    fn fill_triangle(&mut self, points: [Vec2; 3], colors: [Vec3; 3]) {
+        let [a, b, c] = points;
+        let area = edge(a, b, c);
+        // Dos du triangle : sens horaire à l'écran
+        if area <= 0.0 {
+            return;
+        }
+
+        let (width, height) = (self.framebuffer.width, self.framebuffer.height);
+        let min = a.min(b).min(c).floor().max(Vec2::ZERO);
+        let max = a.max(b).max(c).ceil().min(Vec2::new(width as f32, height as f32));
+
+        for y in min.y as u32..max.y as u32 {
+            for x in min.x as u32..max.x as u32 {
+                let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let weights = [(b, c), (c, a), (a, b)].map(|(from, to)| (edge(from, to, p), from, to));
+                let inside = weights
+                    .iter()
+                    .all(|&(weight, from, to)| weight > 0.0 || (weight == 0.0 && is_top_left(from, to)));
+                if !inside {
+                    continue;
+                }
+
+                let color = (colors[0] * weights[0].0 + colors[1] * weights[1].0 + colors[2] * weights[2].0) / area;
+                let index = ((y * width + x) * 4) as usize;
+                self.framebuffer.pixels[index..index + 4].copy_from_slice(&encode(color.extend(1.0).into()));
+            }
+        }
+    }
+}
+
+impl Renderer for CpuRenderer {
+    fn resize(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            self.framebuffer = Image::new(width, height, encode(CLEAR_COLOR));
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.framebuffer.width, self.framebuffer.height)
+    }
+
+    fn render(&mut self, scene: &Scene) -> Result<(), RenderError> {
+        let clear = encode(CLEAR_COLOR);
+        for pixel in self.framebuffer.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&clear);
+        }
+
+        let size = Vec2::new(self.framebuffer.width as f32, self.framebuffer.height as f32);
+        for object in scene.objects() {
+            let vertices = object.mesh().vertices();
+            for instance in object.instances() {
+                let model = instance.model();
+
+                for triangle in object.mesh().indices().chunks_exact(3) {
+                    let corners = [0, 1, 2].map(|corner| vertices[triangle[corner] as usize]);
+                    let points = corners.map(|vertex| {
+                        let clip = model.transform_point3(Vec3::from(vertex.position));
+                        // Sommets arrondis au 1/256 de pixel, comme la rastérisation en virgule fixe des GPU
+                        (Vec2::new(clip.x + 1.0, 1.0 - clip.y) * 0.5 * size * SUBPIXELS).round() / SUBPIXELS
+                    });
+                    self.fill_triangle(points, corners.map(|vertex| Vec3::from(vertex.color)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_pixels(&self) -> anyhow::Result<Image> {
+        Ok(self.framebuffer.clone())
+    }
+}
+
+// Positif quand `p` est à gauche de `from` → `to` à l'écran, c'est-à-dire à l'intérieur d'un triangle de face
+fn edge(from: Vec2, to: Vec2, p: Vec2) -> f32 {
+    (p.x - from.x) * (to.y - from.y) - (p.y - from.y) * (to.x - from.x)
+}
+
+// Un pixel exactement sur un bord n'est dessiné que pour les bords haut et gauche, comme sur GPU.
+// Dans le sens trigonométrique à l'écran, un bord haut va vers la gauche et un bord gauche descend.
+fn is_top_left(from: Vec2, to: Vec2) -> bool {
+    (from.y == to.y && to.x < from.x) || to.y > from.y
+}
+
+// Couleur linéaire vers RGBA 8 bits sRGB, comme la texture hors écran
+fn encode(color: [f32; 4]) -> [u8; 4] {
+    let srgb = |c: f32| {
+        let c = c.clamp(0.0, 1.0);
+        if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+    };
+    let byte = |c: f32| (c * 255.0).round() as u8;
+    [byte(srgb(color[0])), byte(srgb(color[1])), byte(srgb(color[2])), byte(color[3].clamp(0.0, 1.0))]
+}
@@ -1,46 +1,29 @@
-use wgpu::util::{BufferInitDescriptor, DeviceExt};
 use crate::render_backend::buffer::Vertex;
 
+/// Sommets et triangles d'un objet, en coordonnées locales ; chaque renderer en garde sa propre copie
+#[derive(Clone, Debug)]
 pub struct Mesh {
-    pub vertex_buffer: wgpu::Buffer,
-    pub index_buffer: wgpu::Buffer,
-    pub num_indices: u32,
+    vertices: Vec<Vertex>,
+    indices: Vec<u16>,
 }
 
 impl Mesh {
-    pub fn from_vertices(
-        device: &wgpu::Device,
-        vertices: &[Vertex],
-        indices: &[u16],
-    ) -> Self {
-        let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
-
-        let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(indices),
-            usage: wgpu::BufferUsages::INDEX,
-        });
-
+    pub fn from_vertices(vertices: &[Vertex], indices: &[u16]) -> Self {
         Self {
-            vertex_buffer,
-            index_buffer,
-            num_indices: indices.len() as u32,
+            vertices: vertices.to_vec(),
+            indices: indices.to_vec(),
         }
     }
 
-    pub fn vertex_buffer(&self) -> &wgpu::Buffer {
-        &self.vertex_buffer
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
     }
 
-    pub fn index_buffer(&self) -> &wgpu::Buffer {
-        &self.index_buffer
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
     }
 
     pub fn num_indices(&self) -> u32 {
-        self.num_indices
+        self.indices.len() as u32
     }
-}
\ No newline at end of file
+}
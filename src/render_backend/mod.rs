@@ -1,6 +1,14 @@
 pub mod state;
+#[cfg(feature = "render")]
 pub mod context;
-pub(crate) mod buffer;
-mod instance;
-mod mesh;
-mod scene;
\ No newline at end of file
+#[cfg(feature = "render")]
+pub mod backend;
+pub mod image;
+pub mod buffer;
+pub mod instance;
+pub mod mesh;
+pub mod scene;
+pub mod renderer;
+pub mod cpu_renderer;
+#[cfg(feature = "render")]
+pub mod wgpu_renderer;
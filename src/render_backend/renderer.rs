@@ -0,0 +1,39 @@
+use std::fmt;
+use crate::render_backend::image::Image;
+use crate::render_backend::scene::Scene;
+
+/// Couleur de fond, en RGBA linéaire
+pub const CLEAR_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+#[derive(Debug)]
+pub enum RenderError {
+    /// La surface de la fenêtre est perdue ou périmée : il faut la reconfigurer avec `resize`
+    SurfaceLost,
+    Failed(String),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::SurfaceLost => write!(f, "window surface lost or outdated"),
+            RenderError::Failed(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Dessine une `Scene` : effacer l'image, puis chaque maillage pour chacune de ses instances.
+///
+/// Les positions sont en coordonnées de découpage (-1 à 1, y vers le haut) ; seuls les triangles
+/// dans le sens trigonométrique sont dessinés.
+pub trait Renderer {
+    fn resize(&mut self, width: u32, height: u32);
+
+    fn size(&self) -> (u32, u32);
+
+    fn render(&mut self, scene: &Scene) -> Result<(), RenderError>;
+
+    /// Pixels de la dernière image rendue, quand la cible peut être relue
+    fn read_pixels(&self) -> anyhow::Result<Image>;
+}
@@ -1,19 +1,21 @@
+use glam::Vec2;
 use crate::render_backend::mesh::Mesh;
-use crate::render_backend::instance::InstanceBuffer;
+use crate::render_backend::instance::Instance;
 
+/// Un maillage dessiné une fois par instance
 pub struct SceneObject {
     mesh: Mesh,
-    instance_buffer: InstanceBuffer,
+    instances: Vec<Instance>,
 }
 
 impl SceneObject {
     pub fn new(
         mesh: Mesh,
-        instance_buffer: InstanceBuffer,
+        instances: Vec<Instance>,
     ) -> Self {
         Self {
             mesh,
-            instance_buffer,
+            instances,
         }
     }
 
@@ -21,15 +23,18 @@ impl SceneObject {
         &self.mesh
     }
 
-    pub fn instance_buffer(&self) -> &InstanceBuffer {
-        &self.instance_buffer
+    pub fn instances(&self) -> &[Instance] {
+        &self.instances
     }
 
-    pub fn instance_buffer_mut(&mut self) -> &mut InstanceBuffer {
-        &mut self.instance_buffer
+    pub fn update_instance(&mut self, index: usize, position: Vec2) {
+        if let Some(instance) = self.instances.get_mut(index) {
+            instance.position = position;
+        }
     }
 }
 
+/// Ce qu'il faut dessiner, indépendamment du `Renderer` qui le dessine
 pub struct Scene {
     objects: Vec<SceneObject>,
 }
@@ -59,4 +64,4 @@ impl Default for Scene {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
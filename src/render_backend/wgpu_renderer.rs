@@ -0,0 +1,213 @@
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use crate::render_backend::buffer::Vertex;
+use crate::render_backend::context::WgpuContext;
+use crate::render_backend::image::Image;
+use crate::render_backend::instance::{Instance, InstanceRaw};
+use crate::render_backend::renderer::{RenderError, Renderer, CLEAR_COLOR};
+use crate::render_backend::scene::{Scene, SceneObject};
+
+/// Buffers GPU d'un objet de la scène
+struct GpuObject {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    num_indices: u32,
+    instance_buffer: wgpu::Buffer,
+    num_instances: usize,
+}
+
+impl GpuObject {
+    fn new(device: &wgpu::Device, object: &SceneObject) -> Self {
+        let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Vertex Buffer"),
+            contents: bytemuck::cast_slice(object.mesh().vertices()),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Index Buffer"),
+            contents: bytemuck::cast_slice(object.mesh().indices()),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            num_indices: object.mesh().num_indices(),
+            instance_buffer: instance_buffer(device, object.instances()),
+            num_instances: object.instances().len(),
+        }
+    }
+}
+
+fn instance_buffer(device: &wgpu::Device, instances: &[Instance]) -> wgpu::Buffer {
+    let instance_data: Vec<InstanceRaw> = instances.iter().map(Instance::to_raw).collect();
+
+    device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("Instance Buffer"),
+        contents: bytemuck::cast_slice(&instance_data),
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+    })
+}
+
+/// Rendu GPU, dans une fenêtre ou une texture hors écran selon le `WgpuContext`
+pub struct WgpuRenderer {
+    context: WgpuContext,
+    render_pipeline: wgpu::RenderPipeline,
+    /// Les maillages sont envoyés une fois ; seules les instances sont mises à jour à chaque image
+    objects: Vec<GpuObject>,
+}
+
+impl WgpuRenderer {
+    pub fn new(context: WgpuContext) -> Self {
+        let shader = context
+            .device
+            .create_shader_module(wgpu::include_wgsl!("../shaders/shader.wgsl"));
+
+        let render_pipeline = context.device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Render Pipeline"),
+                layout: None,
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                    buffers: &[
+                        Vertex::desc(),
+                        InstanceRaw::desc(),
+                    ],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: Some(wgpu::Face::Back),
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    compilation_options: Default::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: context.format(),
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+                cache: None,
+            });
+
+        Self {
+            context,
+            render_pipeline,
+            objects: Vec::new(),
+        }
+    }
+
+    pub fn context(&self) -> &WgpuContext {
+        &self.context
+    }
+
+    // Positions des instances de la scène → buffers GPU
+    fn upload(&mut self, scene: &Scene) {
+        let device = &self.context.device;
+        if self.objects.len() != scene.objects().len() {
+            self.objects = scene.objects().iter().map(|object| GpuObject::new(device, object)).collect();
+            return;
+        }
+
+        for (gpu, object) in self.objects.iter_mut().zip(scene.objects()) {
+            if gpu.num_instances == object.instances().len() {
+                let instance_data: Vec<InstanceRaw> = object.instances().iter().map(Instance::to_raw).collect();
+                self.context.queue.write_buffer(&gpu.instance_buffer, 0, bytemuck::cast_slice(&instance_data));
+            } else {
+                gpu.instance_buffer = instance_buffer(device, object.instances());
+                gpu.num_instances = object.instances().len();
+            }
+        }
+    }
+}
+
+impl Renderer for WgpuRenderer {
+    fn resize(&mut self, width: u32, height: u32) {
+        self.context.resize(width, height);
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.context.size()
+    }
+
+    fn render(&mut self, scene: &Scene) -> Result<(), RenderError> {
+        if !self.context.is_configured() {
+            return Ok(());
+        }
+        self.upload(scene);
+
+        let (output, view) = self.context.acquire().map_err(|e| match e {
+            wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated => RenderError::SurfaceLost,
+            e => RenderError::Failed(e.to_string()),
+        })?;
+
+        let mut encoder = self
+            .context
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+
+        {
+            let [r, g, b, a] = CLEAR_COLOR.map(f64::from);
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color { r, g, b, a }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            render_pass.set_pipeline(&self.render_pipeline);
+
+            for object in &self.objects {
+                render_pass.set_vertex_buffer(0, object.vertex_buffer.slice(..));
+                render_pass.set_vertex_buffer(1, object.instance_buffer.slice(..));
+                render_pass.set_index_buffer(
+                    object.index_buffer.slice(..),
+                    wgpu::IndexFormat::Uint16,
+                );
+
+                render_pass.draw_indexed(
+                    0..object.num_indices,
+                    0,
+                    0..object.num_instances as u32,
+                );
+            }
+        }
+
+        self.context.queue.submit(std::iter::once(encoder.finish()));
+        if let Some(output) = output {
+            output.present();
+        }
+
+        Ok(())
+    }
+
+    fn read_pixels(&self) -> anyhow::Result<Image> {
+        self.context.read_pixels()
+    }
+}
@@ -0,0 +1,375 @@
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+use crate::engine::Engine;
+use crate::game_state::GameEvent;
+use crate::scoring::MatchRules;
+
+/// Version du format ; la version 1, sans empreintes, reste lisible
+pub const REPLAY_VERSION: u32 = 2;
+const HEADER: &str = "rs_pong-replay";
+
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    MissingHeader,
+    UnsupportedVersion { found: u32 },
+    MissingField { name: &'static str },
+    UnknownField { line: usize, name: String },
+    UnknownEvent { line: usize, name: String },
+    Syntax { line: usize },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::MissingHeader => write!(f, "not a replay file, expected `{HEADER} <version>`"),
+            ReplayError::UnsupportedVersion { found } => {
+                write!(f, "unsupported replay version {found}, expected {REPLAY_VERSION}")
+            }
+            ReplayError::MissingField { name } => write!(f, "missing field `{name}`"),
+            ReplayError::UnknownField { line, name } => write!(f, "line {line}: unknown field `{name}`"),
+            ReplayError::UnknownEvent { line, name } => write!(f, "line {line}: unknown event `{name}`"),
+            ReplayError::Syntax { line } => write!(f, "line {line}: syntax error"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Événements extérieurs enregistrables ; `BallOut` et `MatchOver` sont produits par le moteur lui-même
+fn event_name(event: GameEvent) -> Option<&'static str> {
+    match event {
+        GameEvent::Start => Some("Start"),
+        GameEvent::Serve => Some("Serve"),
+        GameEvent::TogglePause => Some("TogglePause"),
+        GameEvent::Restart => Some("Restart"),
+        GameEvent::BallOut(_) | GameEvent::MatchOver(_) => None,
+    }
+}
+
+fn event_from_name(name: &str) -> Option<GameEvent> {
+    [GameEvent::Start, GameEvent::Serve, GameEvent::TogglePause, GameEvent::Restart]
+        .into_iter()
+        .find(|&event| event_name(event) == Some(name))
+}
+
+/// Partie enregistrée : configuration initiale, graine et entrées des raquettes à chaque pas
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub rules: MatchRules,
+    pub seed: u64,
+    pub tick_rate: f32,
+    /// Direction appliquée à chaque raquette, un élément par pas
+    pub inputs: Vec<[f32; 2]>,
+    /// Événements envoyés au moteur, avec le nombre de pas déjà simulés à ce moment
+    pub events: Vec<(u64, GameEvent)>,
+    /// Empreinte de la scène après chaque pas, voir `Scene::checksum` ; vide pour un fichier version 1
+    pub checksums: Vec<u64>,
+}
+
+/// Premier pas où la simulation rejouée ne correspond plus à l'enregistrement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub tick: u64,
+    pub expected: u64,
+    pub found: u64,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tick {}: expected checksum {:016x}, found {:016x}", self.tick, self.expected, self.found)
+    }
+}
+
+impl Replay {
+    pub fn new(rules: MatchRules, seed: u64, tick_rate: f32) -> Self {
+        Self {
+            rules,
+            seed,
+            tick_rate,
+            inputs: Vec::new(),
+            events: Vec::new(),
+            checksums: Vec::new(),
+        }
+    }
+
+    /// Nombre de pas enregistrés
+    pub fn len(&self) -> u64 {
+        self.inputs.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f32(self.len() as f32 / self.tick_rate)
+    }
+
+    pub fn record_event(&mut self, tick: u64, event: GameEvent) {
+        if event_name(event).is_some() {
+            self.events.push((tick, event));
+        }
+    }
+
+    pub fn record_tick(&mut self, input: [f32; 2], checksum: u64) {
+        self.inputs.push(input);
+        self.checksums.push(checksum);
+    }
+
+    /// Rejoue la partie sans fenêtre et compare les empreintes pas à pas.
+    /// Renvoie le nombre de pas vérifiés, ou le premier pas divergent.
+    pub fn verify(&self) -> Result<u64, Divergence> {
+        let mut engine = Engine::from_replay(self);
+        let dt = engine.fixed_dt();
+
+        for &expected in &self.checksums {
+            engine.step(dt);
+            engine.events.clear();
+
+            let found = engine.physics.scene.checksum();
+            if found != expected {
+                return Err(Divergence {
+                    tick: engine.tick(),
+                    expected,
+                    found,
+                });
+            }
+        }
+
+        Ok(self.checksums.len() as u64)
+    }
+
+    pub fn to_text(&self) -> String {
+        let rules = &self.rules;
+        let mut text = format!("{HEADER} {REPLAY_VERSION}\n");
+        text.push_str(&format!("seed = {}\n", self.seed));
+        text.push_str(&format!("tick_rate = {}\n", self.tick_rate));
+        text.push_str(&format!("points_to_win = {}\n", rules.points_to_win));
+        text.push_str(&format!("win_by = {}\n", rules.win_by));
+        text.push_str(&format!("best_of = {}\n", rules.best_of));
+        text.push_str(&format!("serves_per_turn = {}\n", rules.serves_per_turn));
+
+        for (tick, event) in &self.events {
+            text.push_str(&format!("event {tick} {}\n", event_name(*event).unwrap_or_default()));
+        }
+        // `{}` sur un f32 donne l'écriture la plus courte qui se relit à l'identique
+        for (index, [one, two]) in self.inputs.iter().enumerate() {
+            match self.checksums.get(index) {
+                Some(checksum) => text.push_str(&format!("input {one} {two} {checksum:016x}\n")),
+                None => text.push_str(&format!("input {one} {two}\n")),
+            }
+        }
+
+        text
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, ReplayError> {
+        let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+
+        match lines.next().and_then(|(_, line)| line.strip_prefix(HEADER)) {
+            Some(version) => match version.trim().parse() {
+                Ok(1 | REPLAY_VERSION) => {}
+                Ok(found) => return Err(ReplayError::UnsupportedVersion { found }),
+                Err(_) => return Err(ReplayError::MissingHeader),
+            },
+            None => return Err(ReplayError::MissingHeader),
+        }
+
+        let mut seed = None;
+        let mut tick_rate = None;
+        let mut rules = MatchRules::default();
+        let mut inputs = Vec::new();
+        let mut events = Vec::new();
+        let mut checksums = Vec::new();
+
+        for (line_number, line) in lines {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || ReplayError::Syntax { line: line_number };
+
+            if let Some(values) = line.strip_prefix("input ") {
+                let mut values = values.split_whitespace();
+                let mut input = || values.next().and_then(|value| value.parse::<f32>().ok());
+                inputs.push([input().ok_or_else(invalid)?, input().ok_or_else(invalid)?]);
+
+                if let Some(checksum) = values.next() {
+                    checksums.push(u64::from_str_radix(checksum, 16).map_err(|_| invalid())?);
+                }
+                if values.next().is_some() {
+                    return Err(invalid());
+                }
+            } else if let Some(rest) = line.strip_prefix("event ") {
+                let (tick, name) = rest.trim().split_once(' ').ok_or_else(invalid)?;
+                let tick = tick.parse().map_err(|_| invalid())?;
+                let event = event_from_name(name.trim()).ok_or_else(|| ReplayError::UnknownEvent {
+                    line: line_number,
+                    name: name.trim().to_string(),
+                })?;
+                events.push((tick, event));
+            } else {
+                let (name, value) = line.split_once('=').ok_or_else(invalid)?;
+                let (name, value) = (name.trim(), value.trim());
+
+                match name {
+                    "seed" => seed = Some(value.parse().map_err(|_| invalid())?),
+                    "tick_rate" => tick_rate = Some(value.parse().map_err(|_| invalid())?),
+                    "points_to_win" => rules.points_to_win = value.parse().map_err(|_| invalid())?,
+                    "win_by" => rules.win_by = value.parse().map_err(|_| invalid())?,
+                    "best_of" => rules.best_of = value.parse().map_err(|_| invalid())?,
+                    "serves_per_turn" => rules.serves_per_turn = value.parse().map_err(|_| invalid())?,
+                    _ => {
+                        return Err(ReplayError::UnknownField {
+                            line: line_number,
+                            name: name.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        // Empreintes absentes (version 1) ou présentes à chaque pas
+        if !checksums.is_empty() && checksums.len() != inputs.len() {
+            return Err(ReplayError::MissingField { name: "checksum" });
+        }
+
+        Ok(Self {
+            rules,
+            seed: seed.ok_or(ReplayError::MissingField { name: "seed" })?,
+            tick_rate: tick_rate.ok_or(ReplayError::MissingField { name: "tick_rate" })?,
+            inputs,
+            events,
+            checksums,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Self::from_text(&text)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_text())?;
+        Ok(())
+    }
+}
+
+/// Lecture d'un replay par le moteur : remplace les contrôleurs et les événements extérieurs
+pub(crate) struct Playback {
+    replay: Replay,
+    next_event: usize,
+}
+
+impl Playback {
+    pub(crate) fn new(replay: Replay) -> Self {
+        Self { replay, next_event: 0 }
+    }
+
+    pub(crate) fn is_finished(&self, tick: u64) -> bool {
+        tick >= self.replay.len()
+    }
+
+    /// Événements à rejouer avant le pas qui suit `tick`
+    pub(crate) fn events_before(&mut self, tick: u64) -> Vec<GameEvent> {
+        let pending = &self.replay.events[self.next_event..];
+        let count = pending.iter().take_while(|(at, _)| *at <= tick).count();
+        self.next_event += count;
+        pending[..count].iter().map(|(_, event)| *event).collect()
+    }
+
+    /// Entrées du pas numéro `tick` (à partir de 1)
+    pub(crate) fn input(&self, tick: u64) -> Option<[f32; 2]> {
+        let index = tick.checked_sub(1)?;
+        self.replay.inputs.get(index as usize).copied()
+    }
+}
+
+/// Lecteur de replay avec pause, recherche et vitesse variable
+pub struct ReplayPlayer {
+    replay: Replay,
+    pub engine: Engine,
+    paused: bool,
+    speed: f32,
+    base_steps_per_frame: u32,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let engine = Engine::from_replay(&replay);
+        Self {
+            base_steps_per_frame: engine.max_steps_per_frame,
+            engine,
+            replay,
+            paused: false,
+            speed: 1.0,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.engine.tick()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.tick() >= self.replay.len()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Facteur de vitesse : 0.25 pour un ralenti, 4.0 pour une avance rapide
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.0);
+    }
+
+    pub fn update(&mut self, frame_time: Duration) {
+        if self.paused || self.is_finished() {
+            return;
+        }
+
+        // En avance rapide, il faut rattraper plus de pas par frame
+        self.engine.max_steps_per_frame = self.base_steps_per_frame * self.speed.ceil().max(1.0) as u32;
+        self.engine.update(frame_time.mul_f32(self.speed));
+    }
+
+    /// Avance d'un seul pas, même en pause
+    pub fn step_once(&mut self) {
+        if !self.is_finished() {
+            let dt = self.engine.fixed_dt();
+            self.engine.step(dt);
+            self.engine.reset_interpolation();
+        }
+    }
+
+    /// Place la lecture juste après le pas `tick` ; revenir en arrière rejoue depuis le début
+    pub fn seek(&mut self, tick: u64) {
+        let tick = tick.min(self.replay.len());
+        if tick < self.tick() {
+            self.engine = Engine::from_replay(&self.replay);
+        }
+
+        let dt = self.engine.fixed_dt();
+        while self.tick() < tick {
+            self.engine.step(dt);
+        }
+        self.engine.events.clear();
+        self.engine.reset_interpolation();
+    }
+}
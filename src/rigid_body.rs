@@ -1,11 +1,39 @@
-use glam::{vec2, Vec2};
+use glam::Vec2;
 use crate::box_collider::Collider;
 
+#[derive(Clone, Copy, Debug)]
 pub struct RigidBody {
     pub mass: f32,
     pub restitution: f32,
 }
 
+impl RigidBody {
+    pub fn new(mass: f32, restitution: f32) -> Self {
+        Self { mass, restitution }
+    }
+
+    /// Corps de masse infinie : les impulsions ne le déplacent pas (raquettes, murs)
+    pub fn kinematic(restitution: f32) -> Self {
+        Self {
+            mass: f32::INFINITY,
+            restitution,
+        }
+    }
+
+    pub fn inverse_mass(&self) -> f32 {
+        if self.mass.is_finite() && self.mass > 0.0 {
+            1.0 / self.mass
+        } else {
+            0.0
+        }
+    }
+
+    pub fn apply_impulse(&self, velocity: &mut Vec2, impulse: Vec2) {
+        *velocity += impulse * self.inverse_mass();
+    }
+}
+
+#[derive(Clone)]
 pub struct Component {
     pub position: Vec2,
     pub velocity: Vec2,
@@ -16,7 +44,7 @@ impl Component {
     pub fn new(position: Vec2, half_size: Vec2, color: [f32; 3]) -> Self {
         Self {
             position,
-            velocity: vec2(2.0, 0.3),
+            velocity: Vec2::ZERO,
             collider: Collider::new(half_size, color),
         }
     }
@@ -0,0 +1,236 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn opponent(self) -> Self {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchRules {
+    pub points_to_win: u32,
+    pub win_by: u32,
+    pub best_of: u32,
+    pub serves_per_turn: u32,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            points_to_win: 11,
+            win_by: 2,
+            best_of: 1,
+            serves_per_turn: 2,
+        }
+    }
+}
+
+impl MatchRules {
+    pub fn sets_to_win(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchResult {
+    pub winner: Player,
+    pub sets: [u32; 2],
+}
+
+#[derive(Clone)]
+pub struct Scoring {
+    pub rules: MatchRules,
+    points: [u32; 2],
+    sets: [u32; 2],
+    set_server: Player,
+    server: Player,
+    serves_left: u32,
+    result: Option<MatchResult>,
+}
+
+impl Scoring {
+    pub fn new(rules: MatchRules) -> Self {
+        Self {
+            points: [0, 0],
+            sets: [0, 0],
+            set_server: Player::One,
+            server: Player::One,
+            serves_left: rules.serves_per_turn.max(1),
+            result: None,
+            rules,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.rules);
+    }
+
+    pub fn points(&self, player: Player) -> u32 {
+        self.points[player.index()]
+    }
+
+    pub fn sets(&self, player: Player) -> u32 {
+        self.sets[player.index()]
+    }
+
+    pub fn server(&self) -> Player {
+        self.server
+    }
+
+    pub fn result(&self) -> Option<MatchResult> {
+        self.result
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.result.is_some()
+    }
+
+    pub fn award_point(&mut self, player: Player) -> Option<MatchResult> {
+        if self.result.is_some() {
+            return self.result;
+        }
+
+        self.points[player.index()] += 1;
+
+        if self.set_won_by(player) {
+            self.sets[player.index()] += 1;
+            self.points = [0, 0];
+
+            if self.sets[player.index()] >= self.rules.sets_to_win() {
+                self.result = Some(MatchResult {
+                    winner: player,
+                    sets: self.sets,
+                });
+                return self.result;
+            }
+
+            // Le service du set suivant revient à l'autre joueur
+            self.set_server = self.set_server.opponent();
+            self.server = self.set_server;
+            self.serves_left = self.rules.serves_per_turn.max(1);
+        } else {
+            self.rotate_server();
+        }
+
+        None
+    }
+
+    fn set_won_by(&self, player: Player) -> bool {
+        let own = self.points[player.index()];
+        let other = self.points[player.opponent().index()];
+
+        own >= self.rules.points_to_win && own >= other + self.rules.win_by
+    }
+
+    fn rotate_server(&mut self) {
+        self.serves_left = self.serves_left.saturating_sub(1);
+        if self.serves_left > 0 {
+            return;
+        }
+
+        // A égalité en fin de set, le service change à chaque point
+        let deuce_at = self.rules.points_to_win.saturating_sub(1);
+        let deuce = self.points[0] >= deuce_at && self.points[1] >= deuce_at;
+
+        self.server = self.server.opponent();
+        self.serves_left = if deuce { 1 } else { self.rules.serves_per_turn.max(1) };
+    }
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self::new(MatchRules::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Marque des points en alternant, en commençant par le joueur 1
+    fn alternate(scoring: &mut Scoring, points: u32) {
+        for i in 0..points {
+            let player = if i % 2 == 0 { Player::One } else { Player::Two };
+            assert_eq!(scoring.award_point(player), None);
+        }
+    }
+
+    #[test]
+    fn deuce_needs_a_two_point_lead() {
+        let mut scoring = Scoring::default();
+        alternate(&mut scoring, 20);
+        assert_eq!((scoring.points(Player::One), scoring.points(Player::Two)), (10, 10));
+
+        assert_eq!(scoring.award_point(Player::One), None);
+        assert_eq!(scoring.award_point(Player::Two), None);
+        assert_eq!(scoring.award_point(Player::Two), None);
+        assert_eq!((scoring.points(Player::One), scoring.points(Player::Two)), (11, 12));
+
+        let result = scoring.award_point(Player::Two).expect("13-11 wins the set");
+        assert_eq!(result.winner, Player::Two);
+        assert!(scoring.is_over());
+    }
+
+    #[test]
+    fn sets_to_win_follows_best_of() {
+        for (best_of, sets) in [(0, 1), (1, 1), (3, 2), (5, 3)] {
+            let rules = MatchRules {
+                best_of,
+                ..MatchRules::default()
+            };
+            assert_eq!(rules.sets_to_win(), sets, "best of {best_of}");
+        }
+    }
+
+    #[test]
+    fn serve_changes_every_turn_then_every_point_at_deuce() {
+        let mut scoring = Scoring::default();
+        let mut servers = Vec::new();
+        for i in 0..24 {
+            servers.push(scoring.server());
+            let player = if i % 2 == 0 { Player::One } else { Player::Two };
+            scoring.award_point(player);
+        }
+
+        // Deux services chacun jusqu'à 10-10
+        for (point, server) in servers[..20].iter().enumerate() {
+            let expected = if (point / 2) % 2 == 0 { Player::One } else { Player::Two };
+            assert_eq!(*server, expected, "point {point}");
+        }
+        // Puis un seul
+        assert_eq!(servers[20..], [Player::One, Player::Two, Player::One, Player::Two]);
+    }
+
+    #[test]
+    fn winning_a_set_resets_the_points() {
+        let mut scoring = Scoring::new(MatchRules {
+            best_of: 3,
+            ..MatchRules::default()
+        });
+        for _ in 0..10 {
+            scoring.award_point(Player::One);
+        }
+        scoring.award_point(Player::Two);
+        assert_eq!(scoring.award_point(Player::One), None);
+
+        assert_eq!((scoring.sets(Player::One), scoring.sets(Player::Two)), (1, 0));
+        assert_eq!((scoring.points(Player::One), scoring.points(Player::Two)), (0, 0));
+        // Le set suivant est servi par l'autre joueur
+        assert_eq!(scoring.server(), Player::Two);
+        assert!(!scoring.is_over());
+    }
+}
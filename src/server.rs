@@ -0,0 +1,318 @@
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+use crate::engine::Engine;
+use crate::game_state::{GameEvent, GameState};
+use crate::headless::Snapshot;
+use crate::net::NetInput;
+use crate::protocol::{Message, Role};
+use crate::scoring::{MatchRules, Player};
+use crate::spectator::{DeltaFrame, KEYFRAME_INTERVAL};
+
+/// Entrées d'avance gardées par joueur ; au-delà, les plus anciennes sont fusionnées
+const MAX_QUEUED_INPUTS: usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ServerConfig {
+    pub rules: MatchRules,
+    /// Instantanés envoyés par seconde
+    pub snapshot_rate: f32,
+    /// Un client muet depuis plus longtemps est déconnecté
+    pub client_timeout: Duration,
+    /// Retard du flux envoyé aux spectateurs sur la partie réelle
+    pub spectator_delay: Duration,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            rules: MatchRules::default(),
+            snapshot_rate: 20.0,
+            client_timeout: Duration::from_secs(5),
+            spectator_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+struct Client {
+    addr: SocketAddr,
+    role: Role,
+    last_seen: Instant,
+    inputs: VecDeque<(u32, NetInput)>,
+    /// Dernière entrée appliquée, répétée tant qu'aucune nouvelle n'arrive
+    current: NetInput,
+    ack: u32,
+    /// Dernière image envoyée à un spectateur, référence de la suivante
+    sent: Option<Snapshot>,
+    frames_since_keyframe: u32,
+}
+
+/// Serveur faisant autorité : lui seul simule la partie, les clients n'envoient que leurs entrées
+pub struct GameServer {
+    pub config: ServerConfig,
+    socket: UdpSocket,
+    pub engine: Engine,
+    clients: Vec<Client>,
+    /// Instantanés pas encore assez vieux pour les spectateurs
+    history: VecDeque<Snapshot>,
+    accumulator: f32,
+    snapshot_timer: f32,
+}
+
+impl GameServer {
+    pub fn new(config: ServerConfig, bind: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind(bind)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            engine: Engine::with_rules(config.rules),
+            config,
+            socket,
+            clients: Vec::new(),
+            history: VecDeque::new(),
+            accumulator: 0.0,
+            snapshot_timer: 0.0,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub fn has_player(&self, player: Player) -> bool {
+        self.clients.iter().any(|client| client.role == Role::Player(player))
+    }
+
+    /// Une des deux places de joueur est libre
+    pub fn waiting_for_player(&self) -> bool {
+        !(self.has_player(Player::One) && self.has_player(Player::Two))
+    }
+
+    pub fn spectators(&self) -> usize {
+        self.clients.iter().filter(|client| client.role == Role::Spectator).count()
+    }
+
+    /// Avance en temps réel ; renvoie le nombre de pas simulés
+    pub fn update(&mut self, frame_time: Duration) -> u32 {
+        let dt = self.engine.fixed_dt();
+        self.accumulator += frame_time.as_secs_f32();
+
+        let mut steps = 0;
+        while self.accumulator >= dt {
+            if steps == self.engine.max_steps_per_frame {
+                self.accumulator %= dt;
+                break;
+            }
+            self.tick();
+            self.accumulator -= dt;
+            steps += 1;
+        }
+
+        steps
+    }
+
+    /// Un pas de serveur : messages reçus, simulation, puis instantané si c'est le moment
+    pub fn tick(&mut self) {
+        let dt = self.engine.fixed_dt();
+        self.receive();
+        self.drop_silent_clients();
+
+        for index in 0..self.clients.len() {
+            let client = &mut self.clients[index];
+            let Role::Player(player) = client.role else {
+                continue;
+            };
+
+            let input = match client.inputs.pop_front() {
+                Some((sequence, input)) => {
+                    client.ack = sequence;
+                    input
+                }
+                None => client.current.predicted(),
+            };
+            client.current = input;
+
+            if input.serve {
+                self.engine.handle_event(GameEvent::Serve);
+            }
+            // Pas de reprise tant que la place de l'adversaire est vide
+            if input.pause && !(self.engine.state() == GameState::Paused && self.waiting_for_player()) {
+                self.engine.handle_event(GameEvent::TogglePause);
+            }
+            self.engine.physics.set_paddle_input(player, input.paddle);
+        }
+
+        self.engine.step(dt);
+        // Personne ne consomme les événements côté serveur
+        self.engine.events.clear();
+
+        self.snapshot_timer += dt;
+        if self.snapshot_timer >= 1.0 / self.config.snapshot_rate {
+            self.snapshot_timer = 0.0;
+            self.broadcast();
+        }
+    }
+
+    fn send(&self, addr: SocketAddr, message: &Message) {
+        if let Err(e) = self.socket.send_to(&message.encode(), addr) {
+            log::debug!("send to {} failed: {}", addr, e);
+        }
+    }
+
+    fn broadcast(&mut self) {
+        let snapshot = Snapshot::of(&self.engine);
+        self.history.push_back(snapshot);
+        let delayed = self.delayed_snapshot();
+
+        for index in 0..self.clients.len() {
+            let client = &mut self.clients[index];
+            let message = match client.role {
+                Role::Player(_) => Message::Snapshot { ack: client.ack, snapshot },
+                Role::Spectator => {
+                    let Some(delayed) = delayed else {
+                        continue;
+                    };
+                    let frame = Self::spectator_frame(client, delayed);
+                    Message::SpectatorFrame(frame)
+                }
+            };
+            let addr = client.addr;
+            self.send(addr, &message);
+        }
+    }
+
+    // Le plus récent instantané ayant au moins `spectator_delay` de retard ; les plus vieux sont oubliés
+    fn delayed_snapshot(&mut self) -> Option<Snapshot> {
+        let delay = (self.config.spectator_delay.as_secs_f32() * self.engine.tick_rate).round() as u64;
+        let newest = self.engine.tick().checked_sub(delay)?;
+
+        while self.history.get(1).is_some_and(|next| next.tick <= newest) {
+            self.history.pop_front();
+        }
+        self.history.front().copied().filter(|snapshot| snapshot.tick <= newest)
+    }
+
+    // Différence avec l'image précédente, ou image complète quand le spectateur doit se recaler
+    fn spectator_frame(client: &mut Client, snapshot: Snapshot) -> DeltaFrame {
+        if client.frames_since_keyframe >= KEYFRAME_INTERVAL {
+            client.sent = None;
+        }
+        let frame = DeltaFrame::between(client.sent.as_ref(), &snapshot);
+        client.frames_since_keyframe = if frame.is_keyframe() { 1 } else { client.frames_since_keyframe + 1 };
+        client.sent = Some(snapshot);
+        frame
+    }
+
+    fn receive(&mut self) {
+        let mut buffer = [0u8; 1500];
+        loop {
+            let (len, addr) = match self.socket.recv_from(&mut buffer) {
+                Ok(received) => received,
+                Err(e) if e.kind() == io::ErrorKind::ConnectionReset => continue,
+                Err(_) => break,
+            };
+            if let Some(message) = Message::decode(&buffer[..len]) {
+                self.handle(addr, message);
+            }
+        }
+    }
+
+    fn handle(&mut self, addr: SocketAddr, message: Message) {
+        let known = self.clients.iter().position(|client| client.addr == addr);
+        if let Some(index) = known {
+            self.clients[index].last_seen = Instant::now();
+        }
+
+        match (message, known) {
+            // Un Join répété (réponse perdue) reçoit à nouveau la même place
+            (Message::Join { .. }, Some(index)) => {
+                let role = self.clients[index].role;
+                self.welcome(addr, role);
+            }
+            (Message::Join { role }, None) => match self.assign(role) {
+                Some(role) => {
+                    log::info!("{} joined as {:?}", addr, role);
+                    self.clients.push(Client {
+                        addr,
+                        role,
+                        last_seen: Instant::now(),
+                        inputs: VecDeque::new(),
+                        current: NetInput::default(),
+                        ack: 0,
+                        sent: None,
+                        frames_since_keyframe: 0,
+                    });
+                    self.welcome(addr, role);
+                }
+                None => self.send(addr, &Message::Rejected),
+            },
+            // Un spectateur ne peut pas jouer
+            (Message::Input { sequence, input }, Some(index)) if self.clients[index].role != Role::Spectator => {
+                let client = &mut self.clients[index];
+                let newest = client.inputs.back().map_or(client.ack, |(sequence, _)| *sequence);
+                if sequence <= newest {
+                    return;
+                }
+
+                client.inputs.push_back((sequence, input));
+                // Trop d'avance : on rattrape en gardant les appuis des entrées sautées
+                while client.inputs.len() > MAX_QUEUED_INPUTS {
+                    let (_, skipped) = client.inputs.pop_front().unwrap();
+                    if let Some((_, next)) = client.inputs.front_mut() {
+                        next.serve |= skipped.serve;
+                        next.pause |= skipped.pause;
+                    }
+                }
+            }
+            // Image de référence perdue côté spectateur : la prochaine sera complète
+            (Message::Resync, Some(index)) => self.clients[index].sent = None,
+            (Message::Leave, Some(index)) => {
+                let client = self.clients.remove(index);
+                self.on_leave(&client);
+            }
+            _ => {}
+        }
+    }
+
+    fn welcome(&self, addr: SocketAddr, role: Role) {
+        let tick_rate = self.engine.tick_rate;
+        self.send(addr, &Message::Welcome { role, tick_rate });
+    }
+
+    // Place libre correspondant à la demande ; les spectateurs ne sont pas limités
+    fn assign(&self, requested: Option<Role>) -> Option<Role> {
+        match requested {
+            Some(Role::Spectator) => Some(Role::Spectator),
+            Some(Role::Player(player)) => (!self.has_player(player)).then_some(Role::Player(player)),
+            None => [Player::One, Player::Two]
+                .into_iter()
+                .find(|&player| !self.has_player(player))
+                .map(Role::Player),
+        }
+    }
+
+    fn drop_silent_clients(&mut self) {
+        let timeout = self.config.client_timeout;
+        let (silent, connected): (Vec<_>, Vec<_>) = self
+            .clients
+            .drain(..)
+            .partition(|client| client.last_seen.elapsed() > timeout);
+        self.clients = connected;
+
+        for client in &silent {
+            self.on_leave(client);
+        }
+    }
+
+    // Sans son adversaire, une partie en cours est mise en pause jusqu'à son remplacement
+    fn on_leave(&mut self, client: &Client) {
+        log::info!("{} left ({:?})", client.addr, client.role);
+        if let Role::Player(player) = client.role {
+            self.engine.physics.set_paddle_input(player, 0.0);
+            if self.engine.state().is_in_play() {
+                self.engine.handle_event(GameEvent::TogglePause);
+            }
+        }
+    }
+}
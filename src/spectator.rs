@@ -0,0 +1,413 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+use glam::Vec2;
+use crate::engine::Engine;
+use crate::game_state::GameState;
+use crate::headless::Snapshot;
+use crate::protocol::{decode_frame, encode_frame};
+use crate::replay::Replay;
+use crate::scoring::Player;
+
+/// Une image complète au plus tard toutes les `KEYFRAME_INTERVAL` images, pour qui en aurait perdu
+pub const KEYFRAME_INTERVAL: u32 = 20;
+
+const STREAM_HEADER: &[u8] = b"rs_pong-stream 1\n";
+
+/// Image du flux spectateur : seuls les champs qui ont changé depuis l'image `base` sont présents
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DeltaFrame {
+    pub tick: u64,
+    /// Pas de l'image de référence ; `None` pour une image complète
+    pub base: Option<u64>,
+    pub state: Option<GameState>,
+    pub ball_position: Option<Vec2>,
+    pub ball_velocity: Option<Vec2>,
+    pub paddle_positions: [Option<Vec2>; 2],
+    pub paddle_velocities: [Option<Vec2>; 2],
+    pub points: Option<[u32; 2]>,
+    pub sets: Option<[u32; 2]>,
+    pub server: Option<Player>,
+    pub rally_hits: Option<u32>,
+}
+
+fn changed<T: PartialEq + Copy>(base: Option<&Snapshot>, field: impl Fn(&Snapshot) -> T, snapshot: &Snapshot) -> Option<T> {
+    let value = field(snapshot);
+    match base {
+        Some(base) if field(base) == value => None,
+        _ => Some(value),
+    }
+}
+
+impl DeltaFrame {
+    /// Différence entre `base` et `snapshot` ; image complète sans `base`
+    pub fn between(base: Option<&Snapshot>, snapshot: &Snapshot) -> Self {
+        Self {
+            tick: snapshot.tick,
+            base: base.map(|base| base.tick),
+            state: changed(base, |s| s.state, snapshot),
+            ball_position: changed(base, |s| s.ball_position, snapshot),
+            ball_velocity: changed(base, |s| s.ball_velocity, snapshot),
+            paddle_positions: [0, 1].map(|index| changed(base, |s| s.paddle_positions[index], snapshot)),
+            paddle_velocities: [0, 1].map(|index| changed(base, |s| s.paddle_velocities[index], snapshot)),
+            points: changed(base, |s| s.points, snapshot),
+            sets: changed(base, |s| s.sets, snapshot),
+            server: changed(base, |s| s.server, snapshot),
+            rally_hits: changed(base, |s| s.rally_hits, snapshot),
+        }
+    }
+
+    pub fn is_keyframe(&self) -> bool {
+        self.base.is_none()
+    }
+
+    /// Reconstruit l'instantané ; `None` si l'image de référence n'est pas celle attendue
+    pub fn apply(&self, base: Option<&Snapshot>) -> Option<Snapshot> {
+        let mut snapshot = match (self.base, base) {
+            (None, _) => Snapshot {
+                tick: self.tick,
+                state: self.state?,
+                ball_position: self.ball_position?,
+                ball_velocity: self.ball_velocity?,
+                paddle_positions: [self.paddle_positions[0]?, self.paddle_positions[1]?],
+                paddle_velocities: [self.paddle_velocities[0]?, self.paddle_velocities[1]?],
+                points: self.points?,
+                sets: self.sets?,
+                server: self.server?,
+                rally_hits: self.rally_hits?,
+            },
+            (Some(tick), Some(base)) if base.tick == tick => *base,
+            _ => return None,
+        };
+
+        snapshot.tick = self.tick;
+        snapshot.state = self.state.unwrap_or(snapshot.state);
+        snapshot.ball_position = self.ball_position.unwrap_or(snapshot.ball_position);
+        snapshot.ball_velocity = self.ball_velocity.unwrap_or(snapshot.ball_velocity);
+        for index in 0..2 {
+            snapshot.paddle_positions[index] = self.paddle_positions[index].unwrap_or(snapshot.paddle_positions[index]);
+            snapshot.paddle_velocities[index] = self.paddle_velocities[index].unwrap_or(snapshot.paddle_velocities[index]);
+        }
+        snapshot.points = self.points.unwrap_or(snapshot.points);
+        snapshot.sets = self.sets.unwrap_or(snapshot.sets);
+        snapshot.server = self.server.unwrap_or(snapshot.server);
+        snapshot.rally_hits = self.rally_hits.unwrap_or(snapshot.rally_hits);
+
+        Some(snapshot)
+    }
+}
+
+/// Flux spectateur enregistré : les instantanés reçus, rejouables sans serveur.
+///
+/// Un client ne reçoit jamais les entrées des joueurs, seulement l'état du serveur : il ne peut pas
+/// écrire de `Replay`, qui se rejoue en resimulant les entrées. Il garde donc les images elles-mêmes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StreamRecording {
+    pub tick_rate: f32,
+    pub frames: Vec<Snapshot>,
+}
+
+impl StreamRecording {
+    pub fn new(tick_rate: f32) -> Self {
+        Self {
+            tick_rate,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, snapshot: Snapshot) {
+        self.frames.push(snapshot);
+    }
+
+    /// Vrai si `bytes` commence comme un fichier de flux, pour le distinguer d'un replay
+    pub fn is_stream(bytes: &[u8]) -> bool {
+        bytes.starts_with(STREAM_HEADER)
+    }
+
+    /// Format binaire : en-tête, fréquence, puis les images du flux en delta, préfixées par leur taille
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        out.write_all(STREAM_HEADER)?;
+        out.write_all(&self.tick_rate.to_bits().to_le_bytes())?;
+
+        let mut previous: Option<&Snapshot> = None;
+        for (index, snapshot) in self.frames.iter().enumerate() {
+            let base = if (index as u32).is_multiple_of(KEYFRAME_INTERVAL) { None } else { previous };
+            let frame = encode_frame(&DeltaFrame::between(base, snapshot));
+            out.write_all(&(frame.len() as u16).to_le_bytes())?;
+            out.write_all(&frame)?;
+            previous = Some(snapshot);
+        }
+
+        Ok(())
+    }
+
+    pub fn read(input: &mut impl Read) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+        let mut header = vec![0u8; STREAM_HEADER.len()];
+        input.read_exact(&mut header)?;
+        if header != STREAM_HEADER {
+            return Err(invalid("not a spectator stream"));
+        }
+
+        let mut tick_rate = [0u8; 4];
+        input.read_exact(&mut tick_rate)?;
+        let mut recording = Self::new(f32::from_bits(u32::from_le_bytes(tick_rate)));
+
+        let mut length = [0u8; 2];
+        loop {
+            match input.read_exact(&mut length) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+
+            let mut bytes = vec![0u8; u16::from_le_bytes(length) as usize];
+            input.read_exact(&mut bytes)?;
+            let snapshot = decode_frame(&bytes)
+                .and_then(|frame| frame.apply(recording.frames.last()))
+                .ok_or_else(|| invalid("corrupted frame"))?;
+            recording.push(snapshot);
+        }
+
+        Ok(recording)
+    }
+
+    /// Vérifie la fréquence et l'ordre des images ; renvoie le nombre d'images
+    pub fn verify(&self) -> Result<usize, StreamError> {
+        if !(self.tick_rate.is_finite() && self.tick_rate > 0.0) {
+            return Err(StreamError::InvalidTickRate(self.tick_rate));
+        }
+
+        for (index, pair) in self.frames.windows(2).enumerate() {
+            if pair[1].tick <= pair[0].tick {
+                return Err(StreamError::TickOutOfOrder {
+                    index: index + 1,
+                    previous: pair[0].tick,
+                    tick: pair[1].tick,
+                });
+            }
+        }
+
+        Ok(self.frames.len())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        self.write(&mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut file = io::BufReader::new(std::fs::File::open(path)?);
+        Ok(Self::read(&mut file)?)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StreamError {
+    InvalidTickRate(f32),
+    /// L'image `index` ne vient pas après la précédente
+    TickOutOfOrder { index: usize, previous: u64, tick: u64 },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::InvalidTickRate(rate) => write!(f, "invalid tick rate {rate}"),
+            StreamError::TickOutOfOrder { index, previous, tick } => {
+                write!(f, "frame {index}: tick {tick} does not follow tick {previous}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// Fichier de partie enregistrée, reconnu à son en-tête
+pub enum Recording {
+    Replay(Replay),
+    Stream(StreamRecording),
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        if StreamRecording::is_stream(&bytes) {
+            Ok(Recording::Stream(StreamRecording::read(&mut bytes.as_slice())?))
+        } else {
+            Ok(Recording::Replay(Replay::from_text(std::str::from_utf8(&bytes)?)?))
+        }
+    }
+}
+
+/// Lecture d'un flux enregistré dans un `Engine` d'affichage, avec pause, recherche et vitesse
+pub struct StreamPlayer {
+    recording: StreamRecording,
+    /// Temps de lecture, en pas depuis la première image
+    position: f32,
+    current: usize,
+    paused: bool,
+    speed: f32,
+}
+
+impl StreamPlayer {
+    pub fn new(recording: StreamRecording) -> Self {
+        Self {
+            recording,
+            position: 0.0,
+            current: 0,
+            paused: false,
+            speed: 1.0,
+        }
+    }
+
+    pub fn recording(&self) -> &StreamRecording {
+        &self.recording
+    }
+
+    /// Image affichée en ce moment
+    pub fn snapshot(&self) -> Option<&Snapshot> {
+        self.recording.frames.get(self.current)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current + 1 >= self.recording.frames.len()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed.max(0.0);
+    }
+
+    /// Pas du match enregistré où en est la lecture
+    pub fn tick(&self) -> u64 {
+        self.recording.frames.first().map_or(0, |frame| frame.tick) + self.position as u64
+    }
+
+    pub fn update(&mut self, engine: &mut Engine, frame_time: Duration) {
+        if !self.paused {
+            self.position += frame_time.as_secs_f32() * self.speed * self.recording.tick_rate;
+        }
+        self.show(engine);
+    }
+
+    /// Place la lecture au pas `tick` du match enregistré
+    pub fn seek(&mut self, engine: &mut Engine, tick: u64) {
+        let first = self.recording.frames.first().map_or(0, |frame| frame.tick);
+        self.position = tick.saturating_sub(first) as f32;
+        self.show(engine);
+    }
+
+    fn show(&mut self, engine: &mut Engine) {
+        let Some(first) = self.recording.frames.first().map(|frame| frame.tick) else {
+            return;
+        };
+
+        let tick = first + self.position as u64;
+        self.current = self.recording.frames.partition_point(|frame| frame.tick <= tick).saturating_sub(1);
+        self.recording.frames[self.current].write_to(&mut engine.physics.scene);
+        engine.reset_interpolation();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::vec2;
+
+    // Instantané dont la balle avance à chaque pas, le reste ne changeant que de temps en temps
+    fn snapshot(tick: u64) -> Snapshot {
+        let mut snapshot = Snapshot::of(&Engine::new());
+        snapshot.tick = tick;
+        snapshot.state = GameState::Rally;
+        snapshot.ball_position = vec2(tick as f32 * 0.01, 0.0);
+        snapshot.ball_velocity = vec2(0.6, 0.0);
+        snapshot.points = [(tick / 25) as u32, 0];
+        snapshot
+    }
+
+    #[test]
+    fn delta_only_carries_changed_fields() {
+        let (base, next) = (snapshot(10), snapshot(11));
+        let frame = DeltaFrame::between(Some(&base), &next);
+
+        assert_eq!(frame.base, Some(10));
+        assert_eq!(frame.ball_position, Some(next.ball_position));
+        assert_eq!(frame.ball_velocity, None);
+        assert_eq!(frame.points, None);
+        assert_eq!(frame.apply(Some(&base)), Some(next));
+    }
+
+    #[test]
+    fn unchanged_frame_has_no_fields() {
+        let base = snapshot(10);
+        let same = Snapshot { tick: 11, ..base };
+        let frame = DeltaFrame::between(Some(&base), &same);
+
+        assert_eq!(
+            frame,
+            DeltaFrame {
+                tick: 11,
+                base: Some(10),
+                ..DeltaFrame::default()
+            }
+        );
+        assert_eq!(frame.apply(Some(&base)), Some(same));
+    }
+
+    #[test]
+    fn delta_needs_its_own_base() {
+        let frame = DeltaFrame::between(Some(&snapshot(10)), &snapshot(11));
+
+        assert_eq!(frame.apply(None), None);
+        assert_eq!(frame.apply(Some(&snapshot(9))), None);
+        // Une image complète se suffit à elle-même
+        let keyframe = DeltaFrame::between(None, &snapshot(11));
+        assert!(keyframe.is_keyframe());
+        assert_eq!(keyframe.apply(Some(&snapshot(9))), Some(snapshot(11)));
+    }
+
+    #[test]
+    fn stream_round_trips_across_keyframes() {
+        let mut recording = StreamRecording::new(60.0);
+        for tick in 0..(KEYFRAME_INTERVAL as u64 * 2 + 5) {
+            recording.push(snapshot(tick * 3));
+        }
+
+        let mut bytes = Vec::new();
+        recording.write(&mut bytes).unwrap();
+        assert!(StreamRecording::is_stream(&bytes));
+
+        let read = StreamRecording::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read, recording);
+        assert_eq!(read.verify(), Ok(recording.frames.len()));
+    }
+
+    #[test]
+    fn stream_rejects_a_bad_tick_rate_or_order() {
+        let mut recording = StreamRecording::new(0.0);
+        recording.push(snapshot(5));
+        assert_eq!(recording.verify(), Err(StreamError::InvalidTickRate(0.0)));
+
+        recording.tick_rate = 60.0;
+        recording.push(snapshot(5));
+        assert_eq!(
+            recording.verify(),
+            Err(StreamError::TickOutOfOrder { index: 1, previous: 5, tick: 5 })
+        );
+    }
+}
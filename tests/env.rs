@@ -0,0 +1,93 @@
+use rs_pong::env::{EnvConfig, PongEnv, RewardConfig};
+use rs_pong::headless::PaddleAction;
+use rs_pong::scoring::Player;
+
+const ACTIONS: [PaddleAction; 3] = [PaddleAction::Up, PaddleAction::Idle, PaddleAction::Down];
+
+// Score d'un joueur, comparable même quand un set gagné remet les points à zéro
+fn score(env: &PongEnv, player: Player) -> (u32, u32) {
+    let snapshot = env.simulation().snapshot();
+    (snapshot.sets[player.index()], snapshot.points[player.index()])
+}
+
+#[test]
+fn same_seed_gives_the_same_trajectory() {
+    let mut first = PongEnv::default();
+    let mut second = PongEnv::default();
+
+    for seed in [3, 42] {
+        assert_eq!(first.reset(seed), second.reset(seed));
+        for i in 0..2_000 {
+            let action = ACTIONS[i % 7 % 3];
+            let (a, b) = (first.step(action), second.step(action));
+            assert_eq!(a, b, "seed {seed}, step {i}");
+            if a.done {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn rewards_follow_the_defending_side() {
+    for agent in [Player::One, Player::Two] {
+        // Raquettes immobiles : chaque joueur finit par laisser passer la balle
+        let mut env = PongEnv::new(EnvConfig {
+            agent,
+            opponent: None,
+            rewards: RewardConfig {
+                ball_touched: 0.0,
+                ..RewardConfig::default()
+            },
+            frame_skip: 1,
+            ..EnvConfig::default()
+        });
+        env.reset(7);
+
+        let (mut won, mut lost) = (0, 0);
+        for _ in 0..100_000 {
+            let (own, other) = (score(&env, agent), score(&env, agent.opponent()));
+            let result = env.step(PaddleAction::Idle);
+
+            if result.reward > 0.0 {
+                assert_eq!(result.info.points_won, 1);
+                assert!(score(&env, agent) > own, "{agent:?} rewarded without scoring");
+                won += 1;
+            } else if result.reward < 0.0 {
+                assert_eq!(result.info.points_lost, 1);
+                assert!(score(&env, agent.opponent()) > other, "{agent:?} penalized without conceding");
+                lost += 1;
+            }
+            if result.done || (won > 0 && lost > 0) {
+                break;
+            }
+        }
+        assert!(won > 0 && lost > 0, "{agent:?}: {won} points won, {lost} lost");
+    }
+}
+
+#[test]
+fn observation_is_mirrored_for_player_two() {
+    let config = EnvConfig {
+        opponent: None,
+        ..EnvConfig::default()
+    };
+    let mut left = PongEnv::new(config);
+    let mut right = PongEnv::new(EnvConfig {
+        agent: Player::Two,
+        ..config
+    });
+    left.reset(5);
+    right.reset(5);
+
+    for _ in 0..30 {
+        let (l, r) = (left.step(PaddleAction::Idle).observation, right.step(PaddleAction::Idle).observation);
+        assert_eq!(left.simulation().snapshot(), right.simulation().snapshot());
+
+        // Balle : abscisses retournées, ordonnées inchangées
+        assert_eq!([r[0], r[1], r[2], r[3]], [-l[0], l[1], -l[2], l[3]]);
+        // Raquettes : la sienne d'abord
+        assert_eq!([r[4], r[5], r[6], r[7]], [l[6], l[7], l[4], l[5]]);
+    }
+    assert_ne!(left.observe()[0], 0.0);
+}
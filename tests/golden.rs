@@ -0,0 +1,58 @@
+//! Compare le rendu d'une scène fixe à l'image de référence `tests/golden/scene.png`.
+//! `UPDATE_GOLDEN=1 cargo test --test golden` réécrit la référence après un changement voulu du rendu.
+
+use std::path::Path;
+use glam::vec2;
+use rs_pong::headless::Snapshot;
+#[cfg(feature = "render")]
+use rs_pong::render_backend::backend::GraphicsConfig;
+use rs_pong::render_backend::state::State;
+
+const GOLDEN: &str = "tests/golden/scene.png";
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 90;
+
+// Balle et raquettes loin de leur position de départ
+fn render(mut state: State) -> Vec<u8> {
+    let mut snapshot = Snapshot::of(&state.engine);
+    snapshot.ball_position = vec2(0.31, -0.42);
+    snapshot.paddle_positions[0].y = 0.55;
+    snapshot.paddle_positions[1].y = -0.18;
+    snapshot.write_to(&mut state.engine.physics.scene);
+    state.engine.reset_interpolation();
+
+    state.sync();
+    state.render().unwrap();
+    state.read_pixels().unwrap().to_png()
+}
+
+fn check(png: &[u8], renderer: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(GOLDEN);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, png).unwrap();
+        return;
+    }
+
+    let golden = std::fs::read(&path).unwrap();
+    assert!(
+        png == golden.as_slice(),
+        "{renderer} render differs from {GOLDEN}, rerun with UPDATE_GOLDEN=1 if the change is intended"
+    );
+}
+
+#[test]
+fn software_render_matches_golden() {
+    check(&render(State::software(WIDTH, HEIGHT)), "software");
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn offscreen_render_matches_golden() {
+    // Pas d'adaptateur, même logiciel, sur cette machine : rien à comparer
+    let graphics = GraphicsConfig::default().with_env();
+    let Ok(state) = pollster::block_on(State::offscreen(WIDTH, HEIGHT, &graphics)) else {
+        eprintln!("no wgpu adapter available, skipping offscreen golden test");
+        return;
+    };
+    check(&render(state), "offscreen");
+}
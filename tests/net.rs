@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+use rs_pong::engine::Engine;
+use rs_pong::net::{NetConditions, NetConfig, NetInput, NetSession};
+use rs_pong::scoring::{MatchRules, Player};
+
+const FRAMES: u64 = 300;
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+// Direction changeant de façon imprévisible, pour que le pair se trompe dans ses prédictions
+fn input(player: Player, frame: u64) -> NetInput {
+    let seed = (frame / 13 + player.index() as u64 * 7919).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    NetInput {
+        paddle: [-1.0, 0.0, 1.0][(seed >> 33) as usize % 3],
+        serve: player == Player::One && frame.is_multiple_of(120),
+        pause: false,
+    }
+}
+
+#[test]
+fn sessions_agree_despite_latency_jitter_and_loss() {
+    let conditions = NetConditions {
+        latency: Duration::from_millis(20),
+        jitter: Duration::from_millis(15),
+        loss: 0.2,
+    };
+
+    let mut peers = [Player::One, Player::Two].map(|player| {
+        let config = NetConfig {
+            local_player: player,
+            conditions,
+            ..NetConfig::default()
+        };
+        // Port choisi par le système ; le vrai pair n'est connu qu'une fois les deux sockets ouvertes
+        let session = NetSession::new(config, "127.0.0.1:0", "127.0.0.1:9").unwrap();
+        (session, Engine::with_seed(MatchRules::default(), 9))
+    });
+    let addresses = peers.each_ref().map(|(session, _)| session.local_addr().unwrap());
+    peers[0].0.set_peer(addresses[1]);
+    peers[1].0.set_peer(addresses[0]);
+
+    let started = Instant::now();
+    let settled = |peers: &[(NetSession, Engine); 2]| {
+        peers
+            .iter()
+            .all(|(session, _)| session.confirmed_frame() >= FRAMES && session.acknowledged_frame() >= FRAMES)
+    };
+    while !settled(&peers) {
+        assert!(started.elapsed() < TIMEOUT, "sessions did not confirm {FRAMES} frames");
+        for (session, engine) in &mut peers {
+            if session.frame() < FRAMES {
+                let input = input(session.local_player(), session.frame());
+                session.advance(engine, input);
+            } else {
+                session.poll(engine);
+            }
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    let [(first, one), (second, two)] = &peers;
+    assert_eq!(one.tick(), FRAMES);
+    assert_eq!(two.tick(), FRAMES);
+    assert_eq!(one.physics.scene.checksum(), two.physics.scene.checksum());
+    assert_eq!(one.scoring.points(Player::One), two.scoring.points(Player::One));
+    assert!(first.rollbacks() + second.rollbacks() > 0, "no misprediction was exercised");
+}
@@ -0,0 +1,98 @@
+use rs_pong::ai::{AiController, AiDifficulty};
+use rs_pong::engine::Engine;
+use rs_pong::game_state::GameEvent;
+use rs_pong::headless::Snapshot;
+use rs_pong::replay::{Divergence, Replay, ReplayPlayer};
+use rs_pong::scoring::{MatchRules, Player};
+
+// Échange entre deux IA, enregistré depuis le premier pas
+fn recorded_match(ticks: u64) -> Replay {
+    let mut engine = Engine::with_seed(MatchRules::default(), 11);
+    for player in [Player::One, Player::Two] {
+        engine.set_controller(player, Box::new(AiController::new(AiDifficulty::normal())));
+    }
+    engine.start_recording();
+    engine.handle_event(GameEvent::Start);
+
+    let dt = engine.fixed_dt();
+    for tick in 0..ticks {
+        if tick % 120 == 0 {
+            engine.handle_event(GameEvent::Serve);
+        }
+        engine.step(dt);
+    }
+    engine.stop_recording().unwrap()
+}
+
+#[test]
+fn seeking_matches_straight_playback() {
+    let replay = recorded_match(900);
+    let mut straight = ReplayPlayer::new(replay.clone());
+    let mut seeking = ReplayPlayer::new(replay);
+
+    for _ in 0..600 {
+        straight.step_once();
+    }
+    seeking.seek(800);
+    seeking.seek(200);
+    for _ in 0..400 {
+        seeking.step_once();
+    }
+
+    assert_eq!(seeking.tick(), 600);
+    assert_eq!(Snapshot::of(&seeking.engine), Snapshot::of(&straight.engine));
+}
+
+#[test]
+fn paused_player_only_moves_by_steps() {
+    let mut player = ReplayPlayer::new(recorded_match(300));
+    player.set_speed(4.0);
+    player.toggle_pause();
+
+    player.update(std::time::Duration::from_secs(1));
+    assert_eq!(player.tick(), 0);
+    player.step_once();
+    assert_eq!(player.tick(), 1);
+
+    player.toggle_pause();
+    player.update(std::time::Duration::from_millis(100));
+    assert!(player.tick() > 1);
+}
+
+#[test]
+fn text_round_trip_still_verifies() {
+    let replay = recorded_match(600);
+    let reloaded = Replay::from_text(&replay.to_text()).unwrap();
+
+    assert_eq!(reloaded.inputs, replay.inputs);
+    assert_eq!(reloaded.events, replay.events);
+    assert_eq!(reloaded.verify(), Ok(600));
+}
+
+#[test]
+fn altered_checksum_is_reported_at_its_tick() {
+    let mut replay = recorded_match(600);
+    let found = replay.checksums[249];
+    replay.checksums[249] ^= 1;
+
+    assert_eq!(
+        replay.verify(),
+        Err(Divergence {
+            tick: 250,
+            expected: found ^ 1,
+            found,
+        })
+    );
+}
+
+#[test]
+fn altered_input_diverges_from_its_tick() {
+    let mut replay = recorded_match(600);
+    // Inverse la raquette du joueur 1 en plein échange
+    let input = &mut replay.inputs[399][0];
+    *input = if *input > 0.0 { -1.0 } else { 1.0 };
+
+    let divergence = replay.verify().unwrap_err();
+    assert_eq!(divergence.tick, 400);
+    assert_eq!(divergence.expected, replay.checksums[399]);
+}
@@ -0,0 +1,232 @@
+use std::time::Duration;
+use rs_pong::client::GameClient;
+use rs_pong::engine::Engine;
+use rs_pong::game_state::GameState;
+use rs_pong::net::NetInput;
+use rs_pong::protocol::Role;
+use rs_pong::scoring::Player;
+use rs_pong::server::{GameServer, ServerConfig};
+use rs_pong::spectator::Recording;
+
+const FRAME: Duration = Duration::from_micros(16_667);
+
+/// Serveur sur la boucle locale, deux joueurs et un spectateur, chacun avec son `Engine` d'affichage
+struct Loopback {
+    server: GameServer,
+    clients: Vec<(GameClient, Engine)>,
+}
+
+impl Loopback {
+    fn new(config: ServerConfig) -> Self {
+        let server = GameServer::new(config, "127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let clients = [None, None, Some(Role::Spectator)]
+            .map(|role| (GameClient::new("127.0.0.1:0", addr, role).unwrap(), Engine::new()))
+            .into();
+        Self { server, clients }
+    }
+
+    /// Une frame de chaque client puis du serveur ; `inputs` pour les deux joueurs
+    fn frame(&mut self, inputs: [NetInput; 2]) {
+        for (index, (client, engine)) in self.clients.iter_mut().enumerate() {
+            let input = inputs.get(index).copied().unwrap_or_default();
+            client.update(engine, FRAME, input);
+        }
+        std::thread::sleep(Duration::from_micros(300));
+        self.server.update(FRAME);
+    }
+
+    fn run(&mut self, frames: u32, inputs: impl Fn(u32) -> [NetInput; 2]) {
+        for i in 0..frames {
+            self.frame(inputs(i));
+        }
+    }
+
+    fn join(&mut self) {
+        self.run(30, |_| Default::default());
+        assert_eq!(self.clients[0].0.role(), Some(Role::Player(Player::One)));
+        assert_eq!(self.clients[1].0.role(), Some(Role::Player(Player::Two)));
+        assert_eq!(self.clients[2].0.role(), Some(Role::Spectator));
+    }
+}
+
+fn paddle(engine: &Engine, player: Player) -> f32 {
+    let scene = &engine.physics.scene;
+    scene.component(scene.paddle(player)).position.y
+}
+
+// Raquettes qui montent et descendent en sens opposés, service par le joueur 1
+fn rally_inputs(i: u32) -> [NetInput; 2] {
+    let paddle = if (i / 40).is_multiple_of(2) { 1.0 } else { -1.0 };
+    [
+        NetInput {
+            paddle,
+            serve: i == 0,
+            pause: false,
+        },
+        NetInput {
+            paddle: -paddle,
+            ..Default::default()
+        },
+    ]
+}
+
+#[test]
+fn clients_converge_to_the_server_state() {
+    let mut net = Loopback::new(ServerConfig::default());
+    net.join();
+    net.run(300, rally_inputs);
+
+    // Une fois la partie en pause, plus rien ne bouge : chaque client doit afficher l'état du serveur
+    net.frame([
+        NetInput {
+            pause: true,
+            ..Default::default()
+        },
+        NetInput::default(),
+    ]);
+    net.run(30, |_| Default::default());
+    assert_eq!(net.server.engine.state(), GameState::Paused);
+
+    let expected = net.server.engine.physics.scene.checksum();
+    for (client, engine) in &net.clients[..2] {
+        assert_eq!(client.snapshot().unwrap().state, GameState::Paused);
+        assert_eq!(engine.physics.scene.checksum(), expected);
+    }
+}
+
+#[test]
+fn mispredicted_paddle_is_corrected() {
+    let mut net = Loopback::new(ServerConfig::default());
+    net.join();
+    net.run(120, rally_inputs);
+    assert!(net.server.engine.state().is_in_play());
+
+    // La prédiction locale part dans le décor : le prochain instantané doit la recaler
+    let (_, engine) = &mut net.clients[0];
+    let scene = &mut engine.physics.scene;
+    let wrong = paddle(&net.server.engine, Player::One) + 0.5;
+    scene.component_mut(scene.player1).position.y = wrong;
+
+    net.run(20, |i| rally_inputs(120 + i));
+    let (client, engine) = &net.clients[0];
+    let error = (paddle(engine, Player::One) - paddle(&net.server.engine, Player::One)).abs();
+    assert!(error < 0.1, "paddle still {error} away from the server");
+    assert!(client.pending_inputs() < 20);
+}
+
+#[test]
+fn spectator_receives_delayed_frames() {
+    let delay = Duration::from_secs(1);
+    let mut net = Loopback::new(ServerConfig {
+        spectator_delay: delay,
+        ..ServerConfig::default()
+    });
+    let (spectator, engine) = &mut net.clients[2];
+    spectator.start_recording(engine.tick_rate);
+    net.join();
+    net.run(300, rally_inputs);
+
+    let snapshot = net.clients[2].0.snapshot().expect("no frame reached the spectator");
+    let lag = net.server.engine.tick() - snapshot.tick;
+    let expected = (delay.as_secs_f32() * net.server.engine.tick_rate) as u64;
+    assert!(lag.abs_diff(expected) <= 6, "spectator is {lag} ticks behind");
+
+    // Pendant l'échange, le spectateur extrapole entre deux images : on compare une fois la pause visible
+    net.frame([
+        NetInput {
+            pause: true,
+            ..Default::default()
+        },
+        NetInput::default(),
+    ]);
+    net.run(90, |_| Default::default());
+    let (spectator, engine) = &net.clients[2];
+    let snapshot = spectator.snapshot().unwrap();
+    assert_eq!(snapshot.state, GameState::Paused);
+    assert_eq!(engine.physics.scene.checksum(), {
+        let mut scene = Engine::new().physics.scene;
+        snapshot.write_to(&mut scene);
+        scene.checksum()
+    });
+
+    // Le flux enregistré se relit comme un fichier de partie, images dans l'ordre
+    let recording = net.clients[2].0.stop_recording().unwrap();
+    assert_eq!(recording.tick_rate, net.server.engine.tick_rate);
+    let path = std::env::temp_dir().join(format!("rs_pong-spectator-{}.stream", std::process::id()));
+    recording.save(&path).unwrap();
+    let loaded = Recording::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    match loaded.unwrap() {
+        Recording::Stream(stream) => {
+            assert_eq!(stream, recording);
+            assert_eq!(stream.verify(), Ok(recording.frames.len()));
+        }
+        Recording::Replay(_) => panic!("stream loaded as a replay"),
+    }
+}
+
+#[test]
+fn leaving_pauses_every_phase_of_play() {
+    let mut net = Loopback::new(ServerConfig::default());
+    net.join();
+    // Le joueur 1 lance la partie, le joueur 2 part pendant le décompte du service
+    net.run(1, rally_inputs);
+    assert!(matches!(net.server.engine.state(), GameState::ServeCountdown { .. }));
+
+    net.clients[1].0.leave();
+    net.run(5, |_| Default::default());
+    assert!(!net.server.has_player(Player::Two));
+    assert_eq!(net.server.engine.state(), GameState::Paused);
+}
+
+#[test]
+fn paused_match_waits_for_the_missing_player() {
+    let mut net = Loopback::new(ServerConfig::default());
+    net.join();
+    net.run(60, rally_inputs);
+    let pause = [
+        NetInput {
+            pause: true,
+            ..Default::default()
+        },
+        NetInput::default(),
+    ];
+    net.frame(pause);
+    net.run(5, |_| Default::default());
+    assert_eq!(net.server.engine.state(), GameState::Paused);
+
+    // Le joueur 2 part pendant la pause : le joueur 1 ne peut pas reprendre seul
+    net.clients[1].0.leave();
+    net.clients.remove(1);
+    net.run(5, |_| Default::default());
+    assert!(net.server.waiting_for_player());
+    net.frame(pause);
+    net.run(5, |_| Default::default());
+    assert_eq!(net.server.engine.state(), GameState::Paused);
+
+    // Un remplaçant prend la place libre, la reprise redevient possible
+    let addr = net.server.local_addr().unwrap();
+    net.clients.push((GameClient::new("127.0.0.1:0", addr, None).unwrap(), Engine::new()));
+    net.run(30, |_| Default::default());
+    assert!(!net.server.waiting_for_player());
+    net.frame(pause);
+    net.run(5, |_| Default::default());
+    assert!(net.server.engine.state().is_in_play());
+}
+
+#[test]
+fn non_finite_paddle_input_is_ignored() {
+    let mut net = Loopback::new(ServerConfig::default());
+    net.join();
+    net.run(60, |i| {
+        let mut inputs = rally_inputs(i);
+        inputs[0].paddle = if i.is_multiple_of(2) { f32::NAN } else { f32::INFINITY };
+        inputs
+    });
+
+    assert!(net.server.engine.state().is_in_play());
+    let scene = &net.server.engine.physics.scene;
+    let position = scene.component(scene.player1).position;
+    assert!(position.is_finite(), "paddle at {position}");
+}